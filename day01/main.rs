@@ -1,8 +1,7 @@
-use std::fs;
+use aoc2023::{input::read_input, trace};
 
 fn main() {
-    let contents = fs::read_to_string("./input")
-        .expect("File not found");
+    let contents = read_input();
     
 
     println!("Part 1: {}", part1(&contents));
@@ -28,7 +27,7 @@ fn part1(input: &str) -> u32 {
         })
 }
 
-const WORD_NUMBERS: [&'static str; 9] = [
+const WORD_NUMBERS: [&str; 9] = [
     "one",
     "two",
     "three",
@@ -41,7 +40,7 @@ const WORD_NUMBERS: [&'static str; 9] = [
 ];
 
 fn try_parse_number(input: &str) -> Option<(u32, usize)> {
-    if let Some(digit) = input.chars().nth(0).unwrap().to_digit(10) {
+    if let Some(digit) = input.chars().next().unwrap().to_digit(10) {
         return Some((digit, 1));
     }
 
@@ -93,10 +92,10 @@ fn part2(input: &str) -> u32 {
     
     parsed
         .iter()
-        .fold(0, |a, (x, _str)| {
+        .fold(0, |a, (x, line)| {
             let first = x.first().unwrap_or(&0);
             let last = x.last().unwrap_or(&0);
-            // println!("{} {}", first * 10 + last, str);
+            trace!("{} {}", first * 10 + last, line);
 
             a + (first * 10 + last)
         })
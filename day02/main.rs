@@ -1,4 +1,6 @@
-use std::{fs, str::FromStr};
+use std::str::FromStr;
+
+use aoc2023::{input::read_input, trace};
 
 
 #[derive(Debug, PartialEq)]
@@ -34,8 +36,8 @@ impl FromStr for Game {
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let stripped = s.strip_prefix("Game ").ok_or("Parsing failed")?;
-        let id_str: Vec<char> = stripped.chars().take_while(|c| c.is_digit(10)).collect();
-        let sets: Vec<Vec<RevealedCube>> = (&stripped[(id_str.len() + 2)..])
+        let id_str: Vec<char> = stripped.chars().take_while(|c| c.is_ascii_digit()).collect();
+        let sets: Vec<Vec<RevealedCube>> = stripped[(id_str.len() + 2)..]
             .split(';')
             .map(|set_str| {
                 set_str
@@ -62,13 +64,12 @@ impl FromStr for Game {
 
 
 fn main() {
-    let contents = fs::read_to_string("./input")
-        .expect("File not found");
+    let contents = read_input();
 
     let games: Vec<Game> = contents
         .trim()
         .lines()
-        .map(|line| Game::from_str(line).expect(&format!("Failed to parse line {}", line)))
+        .map(|line| Game::from_str(line).unwrap_or_else(|_| panic!("Failed to parse line {}", line)))
         .collect(); 
 
 
@@ -77,7 +78,7 @@ fn main() {
 }
 
 
-fn part1(games: &Vec<Game>) -> String {
+fn part1(games: &[Game]) -> String {
 
     let red_count = 12;
     let green_count = 13;
@@ -104,6 +105,8 @@ fn part1(games: &Vec<Game>) -> String {
                 return sum + game.id;
             }
 
+            trace!("Game {} needs more cubes than there are", game.id);
+
             sum
         });
 
@@ -111,7 +114,7 @@ fn part1(games: &Vec<Game>) -> String {
 }
 
 
-fn part2(games: &Vec<Game>) -> String {
+fn part2(games: &[Game]) -> String {
     let ans = games
         .iter()
         .fold(0, |ans, game| {
@@ -0,0 +1,167 @@
+use std::str::FromStr;
+
+use aoc2023::bigint::BigInt;
+
+use crate::graph::AdjacencyGraph;
+
+/**
+ * Inclusive bounds on the number of parts a gear touches
+ */
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PartCount {
+    pub min: usize,
+    pub max: usize,
+}
+
+impl PartCount {
+    pub fn exactly(n: usize) -> Self {
+        Self { min: n, max: n }
+    }
+
+    pub fn contains(&self, n: usize) -> bool {
+        self.min <= n && n <= self.max
+    }
+}
+
+/**
+ * Either `n`, `min-max` or `min-` for no upper bound
+ */
+impl FromStr for PartCount {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parse = |s: &str| s
+            .trim()
+            .parse::<usize>()
+            .map_err(|_| format!("Failed to parse part count: {}", s));
+
+        match s.split_once('-') {
+            None => parse(s).map(Self::exactly),
+            Some((min, "")) => Ok(Self { min: parse(min)?, max: usize::MAX }),
+            Some((min, max)) => {
+                let (min, max) = (parse(min)?, parse(max)?);
+
+                if min > max {
+                    return Err(format!("Part count minimum is above its maximum: {}", s));
+                }
+
+                Ok(Self { min, max })
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Combine {
+    Product,
+    Sum,
+}
+
+impl Combine {
+    pub fn apply<'a>(&self, values: impl Iterator<Item = &'a BigInt>) -> BigInt {
+        match self {
+            Combine::Product => values.product(),
+            Combine::Sum => values.sum(),
+        }
+    }
+}
+
+impl FromStr for Combine {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "product" => Ok(Combine::Product),
+            "sum" => Ok(Combine::Sum),
+            _ => Err(format!("Unknown combine function: {}", s)),
+        }
+    }
+}
+
+/**
+ * What makes a symbol a gear and how its ratio is computed
+ */
+#[derive(Debug, Clone, PartialEq)]
+pub struct GearRule {
+    pub symbols: Vec<String>,
+    pub parts: PartCount,
+    pub combine: Combine,
+}
+
+impl Default for GearRule {
+    fn default() -> Self {
+        Self {
+            symbols: vec!["*".to_owned()],
+            parts: PartCount::exactly(2),
+            combine: Combine::Product,
+        }
+    }
+}
+
+impl GearRule {
+    /**
+     * Indices of the graph symbols that are gears under this rule
+     */
+    pub fn gears<'a>(&'a self, graph: &'a AdjacencyGraph) -> impl Iterator<Item = usize> + 'a {
+        graph
+            .symbols()
+            .iter()
+            .enumerate()
+            .filter(|(i, node)| self.is_gear_symbol(&node.symbol) && self.parts.contains(graph.parts_of(*i).len()))
+            .map(|(i, _)| i)
+    }
+
+    pub fn is_gear_symbol(&self, symbol: &str) -> bool {
+        self.symbols.iter().any(|s| s == symbol)
+    }
+
+    pub fn ratio(&self, graph: &AdjacencyGraph, gear: usize) -> BigInt {
+        self.combine.apply(graph.parts_of(gear).iter().map(|part| &graph.part(*part).value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use crate::{gear::{Combine, GearRule, PartCount}, graph::AdjacencyGraph, part2, Schematic};
+
+    const EXAMPLE: &str = include_str!("exampleinput");
+
+    #[test]
+    fn parse_part_count() {
+        assert_eq!(PartCount::from_str("2"), Ok(PartCount::exactly(2)));
+        assert_eq!(PartCount::from_str("1-3"), Ok(PartCount { min: 1, max: 3 }));
+        assert_eq!(PartCount::from_str("3-"), Ok(PartCount { min: 3, max: usize::MAX }));
+        assert!(PartCount::from_str("3-1").is_err());
+        assert!(PartCount::from_str("two").is_err());
+    }
+
+    #[test]
+    fn default_rule_matches_part2() {
+        let schematic = Schematic::from_str(EXAMPLE).unwrap();
+        let graph = AdjacencyGraph::new(&schematic);
+        let rule = GearRule::default();
+
+        assert_eq!(rule.gears(&graph).collect::<Vec<_>>(), vec![0, 5]);
+        assert_eq!(part2(&schematic, &rule), "467835");
+    }
+
+    #[test]
+    fn variant_rules() {
+        let schematic = Schematic::from_str(EXAMPLE).unwrap();
+
+        let sum = GearRule { combine: Combine::Sum, ..GearRule::default() };
+        assert_eq!(part2(&schematic, &sum), (467 + 35 + 755 + 598).to_string());
+
+        let single = GearRule { parts: PartCount::exactly(1), ..GearRule::default() };
+        assert_eq!(part2(&schematic, &single), "617");
+
+        let any = GearRule {
+            symbols: ["*", "#", "+", "$"].map(String::from).to_vec(),
+            parts: PartCount { min: 1, max: usize::MAX },
+            combine: Combine::Sum,
+        };
+        assert_eq!(part2(&schematic, &any), "4361");
+    }
+}
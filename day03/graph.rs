@@ -0,0 +1,205 @@
+use crate::{PartNumber, Schematic, SchematicElement};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SymbolNode {
+    pub symbol: String,
+    pub x: usize,
+    pub y: usize,
+}
+
+/**
+ * Bipartite graph between the symbols of a schematic and its part numbers.
+ * Parts are identified by their index in `Schematic::parts`, symbols by their
+ * index in `symbols`.
+ */
+#[derive(Debug)]
+pub struct AdjacencyGraph<'a> {
+    schematic: &'a Schematic,
+    symbols: Vec<SymbolNode>,
+    symbol_parts: Vec<Vec<usize>>,
+    part_symbols: Vec<Vec<usize>>,
+}
+
+impl<'a> AdjacencyGraph<'a> {
+    pub fn new(schematic: &'a Schematic) -> Self {
+        let mut symbols: Vec<SymbolNode> = vec![];
+        let mut symbol_parts: Vec<Vec<usize>> = vec![];
+        let mut part_symbols: Vec<Vec<usize>> = vec![vec![]; schematic.parts.len()];
+
+        for ((x, y), element) in schematic.grid.iter() {
+            let SchematicElement::Symbol(symbol) = element else {
+                continue;
+            };
+
+            let parts = schematic.parts_adjacent_to(x, y);
+
+            for part in parts.iter() {
+                part_symbols[*part].push(symbols.len());
+            }
+
+            symbols.push(SymbolNode { symbol: symbol.to_string(), x, y });
+            symbol_parts.push(parts);
+        }
+
+        Self {
+            schematic,
+            symbols,
+            symbol_parts,
+            part_symbols,
+        }
+    }
+
+    pub fn symbols(&self) -> &[SymbolNode] {
+        &self.symbols
+    }
+
+    pub fn part(&self, part: usize) -> &PartNumber {
+        &self.schematic.parts[part]
+    }
+
+    pub fn parts_of(&self, symbol: usize) -> &[usize] {
+        &self.symbol_parts[symbol]
+    }
+
+    pub fn symbols_of(&self, part: usize) -> &[usize] {
+        &self.part_symbols[part]
+    }
+
+    pub fn connected_parts(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..self.part_symbols.len()).filter(|part| ! self.part_symbols[*part].is_empty())
+    }
+
+    pub fn isolated_parts(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..self.part_symbols.len()).filter(|part| self.part_symbols[*part].is_empty())
+    }
+
+    /**
+     * Graphviz representation, symbols are boxes and parts are ellipses.
+     * Isolated parts are greyed out.
+     */
+    pub fn to_dot(&self) -> String {
+        let mut out = String::from("graph schematic {\n");
+
+        for (i, node) in self.symbols.iter().enumerate() {
+            out.push_str(&format!(
+                "    s{} [label=\"{} ({}, {})\", shape=box];\n",
+                i, escape(&node.symbol), node.x, node.y,
+            ));
+        }
+
+        for (i, part) in self.schematic.parts.iter().enumerate() {
+            let style = if self.symbols_of(i).is_empty() { ", color=grey, fontcolor=grey" } else { "" };
+
+            out.push_str(&format!("    p{} [label=\"{}\"{}];\n", i, part.value, style));
+        }
+
+        for (i, parts) in self.symbol_parts.iter().enumerate() {
+            for part in parts {
+                out.push_str(&format!("    s{} -- p{};\n", i, part));
+            }
+        }
+
+        out.push_str("}\n");
+
+        out
+    }
+
+    pub fn to_json(&self) -> String {
+        let join = |ids: &[usize]| ids
+            .iter()
+            .map(|id| id.to_string())
+            .collect::<Vec<_>>()
+            .join(",");
+
+        let symbols = self.symbols
+            .iter()
+            .enumerate()
+            .map(|(i, node)| format!(
+                "{{\"id\":{},\"symbol\":\"{}\",\"x\":{},\"y\":{},\"parts\":[{}]}}",
+                i, escape(&node.symbol), node.x, node.y, join(self.parts_of(i)),
+            ))
+            .collect::<Vec<_>>()
+            .join(",");
+
+        let parts = self.schematic.parts
+            .iter()
+            .enumerate()
+            .map(|(i, part)| format!(
+                "{{\"id\":{},\"value\":{},\"row\":{},\"col_start\":{},\"col_end\":{},\"symbols\":[{}]}}",
+                i, part.value, part.row, part.col_start, part.col_end, join(self.symbols_of(i)),
+            ))
+            .collect::<Vec<_>>()
+            .join(",");
+
+        format!("{{\"symbols\":[{}],\"parts\":[{}]}}", symbols, parts)
+    }
+}
+
+/**
+ * Escapes a symbol for use inside a quoted DOT or JSON string
+ */
+fn escape(symbol: &str) -> String {
+    symbol
+        .chars()
+        .map(|c| match c {
+            '"' | '\\' => format!("\\{}", c),
+            c if c.is_control() => format!("\\u{:04x}", c as u32),
+            c => c.to_string(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use crate::{graph::AdjacencyGraph, Schematic};
+
+    const EXAMPLE: &str = include_str!("exampleinput");
+
+    #[test]
+    fn queries() {
+        let schematic = Schematic::from_str(EXAMPLE).unwrap();
+        let graph = AdjacencyGraph::new(&schematic);
+
+        assert_eq!(graph.symbols().len(), 6);
+        assert_eq!(graph.parts_of(0), &[0, 2]);
+        assert_eq!(graph.symbols_of(0), &[0]);
+        assert_eq!(graph.symbols_of(3), &[1]);
+        assert_eq!(graph.isolated_parts().collect::<Vec<_>>(), vec![1, 5]);
+        assert_eq!(graph.connected_parts().count(), 8);
+    }
+
+    #[test]
+    fn dot_export() {
+        let schematic = Schematic::from_str("12.\n.\"#\n..3").unwrap();
+        let graph = AdjacencyGraph::new(&schematic);
+
+        assert_eq!(
+            graph.to_dot(),
+            "graph schematic {\n\
+            \x20   s0 [label=\"\\\" (1, 1)\", shape=box];\n\
+            \x20   s1 [label=\"# (2, 1)\", shape=box];\n\
+            \x20   p0 [label=\"12\"];\n\
+            \x20   p1 [label=\"3\"];\n\
+            \x20   s0 -- p0;\n\
+            \x20   s0 -- p1;\n\
+            \x20   s1 -- p0;\n\
+            \x20   s1 -- p1;\n\
+            }\n",
+        );
+    }
+
+    #[test]
+    fn json_export() {
+        let schematic = Schematic::from_str("12.\n..#\n7..").unwrap();
+        let graph = AdjacencyGraph::new(&schematic);
+
+        assert_eq!(
+            graph.to_json(),
+            "{\"symbols\":[{\"id\":0,\"symbol\":\"#\",\"x\":2,\"y\":1,\"parts\":[0]}],\
+            \"parts\":[{\"id\":0,\"value\":12,\"row\":0,\"col_start\":0,\"col_end\":2,\"symbols\":[0]},\
+            {\"id\":1,\"value\":7,\"row\":2,\"col_start\":0,\"col_end\":1,\"symbols\":[]}]}",
+        );
+    }
+}
@@ -1,196 +1,502 @@
-use std::{fs, str::FromStr, collections::{HashSet, HashMap}};
+use std::{env, fs::File, io::BufReader, iter, str::FromStr};
 
-#[derive(Debug, PartialEq, Eq, Hash)]
+use aoc2023::{bigint::BigInt, debug, grid::{Grid, Neighborhood}, input::{arg_value, input_path, read_input}, text::graphemes};
+use gear::GearRule;
+use graph::AdjacencyGraph;
+use render::{render, Window};
+
+mod gear;
+mod graph;
+mod render;
+mod stream;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 enum SchematicElement {
-    Part((usize, u64)),
-    Symbol(char),
+    /**
+     * Index into `Schematic::parts`
+     */
+    Part(usize),
+    /**
+     * A single column, but possibly several code points
+     */
+    Symbol(Box<str>),
     Empty,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct PartNumber {
+    value: BigInt,
+    row: usize,
+    col_start: usize,
+    /**
+     * Exclusive
+     */
+    col_end: usize,
+}
+
 #[derive(Debug)]
 struct Schematic {
-    width: usize,
-    height: usize,
-    elements: Vec<SchematicElement>,
+    grid: Grid<SchematicElement>,
+    parts: Vec<PartNumber>,
+    /**
+     * Which cells around a symbol it can reach
+     */
+    neighborhood: Neighborhood,
+    /**
+     * How empty cells are drawn
+     */
+    empty: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+enum RaggedRows {
+    #[default]
+    Reject,
+    Pad,
+}
+
+#[derive(Debug, Clone)]
+struct SchematicOptions {
+    /**
+     * What to do with rows that are narrower than the widest one
+     */
+    ragged_rows: RaggedRows,
+    /**
+     * Whether a `-` right before a number is its sign instead of a symbol
+     */
+    signed_numbers: bool,
+    neighborhood: Neighborhood,
+    /**
+     * Cells that are neither part of a number nor a symbol
+     */
+    empty: Vec<String>,
+}
+
+impl Default for SchematicOptions {
+    fn default() -> Self {
+        Self {
+            ragged_rows: RaggedRows::default(),
+            signed_numbers: false,
+            neighborhood: Neighborhood::default(),
+            empty: vec![".".to_owned()],
+        }
+    }
 }
 
 impl FromStr for Schematic {
     type Err = String;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let lines: Vec<_> = s.trim().lines().collect();
-        let height = lines.len();
-        let width = lines
-            .iter()
-            .nth(0)
+        Self::parse(s, &SchematicOptions::default())
+    }
+}
+
+/**
+ * Parses a single line of a schematic, one cell per grapheme. The part numbers
+ * found are appended to `parts` and the cells point at them by their index
+ * there. Only ASCII digits make up numbers, anything else that isn't empty is
+ * a symbol.
+ */
+fn parse_row(
+    line: &str,
+    row: usize,
+    options: &SchematicOptions,
+    parts: &mut Vec<PartNumber>,
+) -> Vec<SchematicElement> {
+    let cells = graphemes(line);
+    let is_digit = |cell: &&str| cell.len() == 1 && cell.as_bytes()[0].is_ascii_digit();
+    let mut i = 0;
+    let mut elements: Vec<SchematicElement> = vec![];
+
+    while i < cells.len() {
+        if options.empty.iter().any(|empty| empty == cells[i]) {
+            i += 1;
+            elements.push(SchematicElement::Empty);
+            continue;
+        }
+
+        let is_sign = options.signed_numbers
+            && cells[i] == "-"
+            && cells.get(i + 1).is_some_and(is_digit);
+
+        if is_digit(&cells[i]) || is_sign {
+            let mut j = i + 1;
+
+            while j < cells.len() && is_digit(&cells[j]) {
+                j += 1;
+            }
+
+            let value = cells[i..j]
+                .concat()
+                .parse()
+                .expect("A run of digits is always a valid number");
+
+            elements.extend((i..j).map(|_| SchematicElement::Part(parts.len())));
+            parts.push(PartNumber { value, row, col_start: i, col_end: j });
+
+            i = j;
+            continue;
+        }
+
+        elements.push(SchematicElement::Symbol(cells[i].into()));
+        i += 1;
+    }
+
+    elements
+}
+
+/**
+ * Whitespace around the schematic that gets trimmed, unless it marks empty
+ * cells and so belongs to the rows at the edges
+ */
+fn is_padding(c: char, options: &SchematicOptions) -> bool {
+    c.is_whitespace() && ! options.empty.iter().any(|empty| empty.chars().eq(iter::once(c)))
+}
+
+impl Schematic {
+    fn parse(s: &str, options: &SchematicOptions) -> Result<Self, String> {
+        let mut parts: Vec<PartNumber> = vec![];
+        let mut rows: Vec<Vec<SchematicElement>> = s
+            .trim_matches(|c| is_padding(c, options))
+            .lines()
+            .enumerate()
+            .map(|(row, line)| parse_row(line, row, options, &mut parts))
+            .collect();
+
+        let width = rows
+            .first()
             .ok_or("Should have at least one element")?
             .len();
 
-        let elements = lines
-            .iter()
-            .enumerate()
-            .flat_map(|(line_idx, line)| {
-                let chars: Vec<_> = line.chars().collect();
-                let mut i = 0;
-                let mut elements: Vec<SchematicElement> = vec![];
-
-                while i < chars.len() {
-                    if chars[i] == '.' {
-                        i += 1;
-                        elements.push(SchematicElement::Empty);
-                        continue;
-                    }
-
-                    if chars[i].is_digit(10) {
-                        let mut j = i + 1;
-                        let mut num_chars = vec![chars[i]];
-
-                        while j < width && chars[j].is_digit(10) {
-                            num_chars.push(chars[j]);
-                            j += 1;
-                        }
-
-                        let part_number = num_chars
-                            .iter()
-                            .collect::<String>()
-                            .parse()
-                            .unwrap();
-
-                        for _ in 0..num_chars.len() {
-                            elements.push(SchematicElement::Part((line_idx * width + i, part_number)));
-                        }
-
-                        i += num_chars.len();
-                        continue;
-                    }
-                    
-                    elements.push(SchematicElement::Symbol(chars[i]));
-                    i += 1;
+        let width = match options.ragged_rows {
+            RaggedRows::Reject => {
+                if let Some((y, row)) = rows.iter().enumerate().find(|(_, row)| row.len() != width) {
+                    return Err(
+                        format!("Row {} has a width of {}, expected {}", y + 1, row.len(), width)
+                    );
                 }
 
-                elements
-            })
-            .collect();
+                width
+            },
+            RaggedRows::Pad => rows.iter().map(|row| row.len()).max().unwrap_or(width),
+        };
+
+        for row in rows.iter_mut() {
+            row.resize_with(width, || SchematicElement::Empty);
+        }
 
         Ok(
             Self {
-                width,
-                height,
-                elements,
+                grid: Grid::from_rows(rows)?,
+                parts,
+                neighborhood: options.neighborhood,
+                empty: options.empty.first().cloned().unwrap_or_else(|| ".".to_owned()),
             }
         )
     }
+
+    fn part_at(&self, x: usize, y: usize) -> Option<usize> {
+        match self.grid.get(x, y) {
+            Some(SchematicElement::Part(i)) => Some(*i),
+            _ => None,
+        }
+    }
+
+    /**
+     * Indices of the parts touching (x, y). Neighbours come out row by row and
+     * a part never spans more than one row, so a part shows up as a run of
+     * equal indices and only the first of the run is kept.
+     */
+    fn parts_adjacent_to(&self, x: usize, y: usize) -> Vec<usize> {
+        let mut adjacent: Vec<usize> = vec![];
+
+        for (nx, ny) in self.grid.neighbors(x, y, &self.neighborhood) {
+            match self.part_at(nx, ny) {
+                Some(i) if adjacent.last() != Some(&i) => adjacent.push(i),
+                _ => continue,
+            }
+        }
+
+        adjacent
+    }
 }
 
 fn main() {
-    let contents = fs::read_to_string("./input")
-        .expect("File not found");
+    let options = SchematicOptions {
+        ragged_rows: if env::args().any(|arg| arg == "--pad") {
+            RaggedRows::Pad
+        } else {
+            RaggedRows::Reject
+        },
+        signed_numbers: env::args().any(|arg| arg == "--signed"),
+        neighborhood: arg_value("--neighborhood")
+            .map(|s| s.parse().unwrap_or_else(|err| panic!("{}", err)))
+            .unwrap_or_default(),
+        empty: arg_value("--empty")
+            .map(|s| graphemes(&s).into_iter().map(String::from).collect())
+            .unwrap_or_else(|| SchematicOptions::default().empty),
+    };
+
+    let default_rule = GearRule::default();
+    let rule = GearRule {
+        symbols: arg_value("--gear-symbols")
+            .map(|s| graphemes(&s).into_iter().map(String::from).collect())
+            .unwrap_or(default_rule.symbols),
+        parts: arg_value("--gear-parts")
+            .map(|s| s.parse().unwrap_or_else(|err| panic!("{}", err)))
+            .unwrap_or(default_rule.parts),
+        combine: arg_value("--gear-combine")
+            .map(|s| s.parse().unwrap_or_else(|err| panic!("{}", err)))
+            .unwrap_or(default_rule.combine),
+    };
+
+    if env::args().any(|arg| arg == "--stream") {
+        let file = File::open(input_path()).expect("File not found");
+
+        let totals = match stream::solve(BufReader::new(file), &options, &rule) {
+            Err(err) => panic!("{}", err),
+            Ok(v) => v,
+        };
+
+        println!("Part 1: {}", totals.part_sum);
+        println!("Part 2: {}", totals.gear_sum);
+        return;
+    }
 
+    let contents = read_input();
 
-    println!("Part 1: {}", part1(&contents));
-    println!("Part 2: {}", part2(&contents));
-}
+    let schematic = match Schematic::parse(&contents, &options) {
+        Err(err) => panic!("{}", err),
+        Ok(v) => v,
+    };
 
-fn get_part_number_at(
-    cache: &mut HashSet<(usize, u64)>,
-    schematic: &Schematic,
-    x: i64,
-    y: i64,
-) -> Option<u64> {
-    let Schematic { width, height, elements } = schematic;
+    if env::args().any(|arg| arg == "--dot") {
+        print!("{}", AdjacencyGraph::new(&schematic).to_dot());
+        return;
+    }
 
-    if x < 0 || x >= *width as i64 || y < 0 || y >= *height as i64 {
-        return None;
+    if env::args().any(|arg| arg == "--json") {
+        println!("{}", AdjacencyGraph::new(&schematic).to_json());
+        return;
     }
-    
-    let i = y as usize * width + x as usize;
 
-    if let SchematicElement::Part((s, num)) = elements[i] {
-        let key = (s, num);
+    if env::args().any(|arg| arg == "--render") {
+        let window = arg_value("--around").map(|around| {
+            let (x, y) = around
+                .split_once(',')
+                .and_then(|(x, y)| Some((x.trim().parse().ok()?, y.trim().parse().ok()?)))
+                .unwrap_or_else(|| panic!("Expected --around x,y, got {}", around));
 
-        if cache.contains(&key) {
-            return None
-        }
+            let radius = arg_value("--radius")
+                .map(|r| r.parse().expect("Failed to parse --radius"))
+                .unwrap_or(10);
+
+            Window { x, y, radius }
+        });
 
-        cache.insert(key);
-        return Some(num);
+        print!("{}", render(&schematic, &rule, window));
     }
 
-    None
+    println!("Part 1: {}", part1(&schematic));
+    println!("Part 2: {}", part2(&schematic, &rule));
 }
 
-fn part1(input: &str) -> String {
-    let schematic = Schematic::from_str(input)
-        .expect("Failed to parse input");
+fn part1(schematic: &Schematic) -> String {
+    let graph = AdjacencyGraph::new(schematic);
 
-    let Schematic { width, height: _, elements } = &schematic;
+    debug!("{} of {} part number(s) touch a symbol", graph.connected_parts().count(), schematic.parts.len());
 
-    let mut sum = 0;
-    let mut set = HashSet::<(usize, u64)>::new();
+    let sum: BigInt = graph
+        .connected_parts()
+        .map(|part| &schematic.parts[part].value)
+        .sum();
 
+    sum.to_string()
+}
 
-    for i in 0..elements.len() {
-        let x = (i % width) as i64;
-        let y = (i / width) as i64;
+fn part2(schematic: &Schematic, rule: &GearRule) -> String {
+    let graph = AdjacencyGraph::new(schematic);
 
-        if ! matches!(elements[i], SchematicElement::Symbol(_)) {
-            continue;
-        }
+    debug!("{} gear(s) found", rule.gears(&graph).count());
 
-        for i in -1..=1 {
-            for j in -1..=1 {
-                if i == 0 && j == 0 {
-                    continue;
-                }
+    let ans: BigInt = rule
+        .gears(&graph)
+        .map(|gear| rule.ratio(&graph, gear))
+        .sum();
 
-                sum += get_part_number_at(&mut set, &schematic, x + i, y + j).unwrap_or(0);
-            }
-        }
+    ans.to_string()
+}
+
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use aoc2023::{bigint::BigInt, grid::Neighborhood};
+
+    use crate::{gear::GearRule, part1, part2, PartNumber, RaggedRows, Schematic, SchematicElement, SchematicOptions};
+
+    const EXAMPLE: &str = include_str!("exampleinput");
+
+    #[test]
+    fn example() {
+        let schematic = Schematic::from_str(EXAMPLE).unwrap();
+
+        assert_eq!(part1(&schematic), "4361");
+        assert_eq!(part2(&schematic, &GearRule::default()), "467835");
     }
 
-    sum.to_string()
-}
+    #[test]
+    fn rejects_ragged_rows() {
+        let err = Schematic::from_str("467..\n...*\n..35.").unwrap_err();
 
-fn part2(input: &str) -> String {
-    let schematic = Schematic::from_str(input)
-        .expect("Failed to parse input");
+        assert_eq!(err, "Row 2 has a width of 4, expected 5");
 
-    let Schematic { width, height: _, elements } = &schematic;
+        let err = Schematic::from_str("467..\n...*..\n..35.").unwrap_err();
 
+        assert_eq!(err, "Row 2 has a width of 6, expected 5");
+    }
 
-    let mut map = HashMap::<usize, HashSet<(usize, u64)>>::new();
-    
-    for i in 0..elements.len() {
-        let x = (i % width) as i64;
-        let y = (i / width) as i64;
+    #[test]
+    fn pads_ragged_rows() {
+        let options = SchematicOptions { ragged_rows: RaggedRows::Pad, ..SchematicOptions::default() };
+        let schematic = Schematic::parse("467\n...*..\n..35", &options).unwrap();
 
-        match elements[i] {
-            SchematicElement::Symbol('*') => {
-                let set = map.entry(i).or_insert(HashSet::new());
+        assert_eq!(schematic.grid.width(), 6);
+        assert_eq!(schematic.grid.height(), 3);
+        assert_eq!(schematic.grid[(5, 0)], SchematicElement::Empty);
+        assert_eq!(schematic.grid[(3, 1)], SchematicElement::Symbol("*".into()));
+        assert_eq!(schematic.grid[(3, 2)], SchematicElement::Part(1));
+    }
 
-                for i in -1..=1 {
-                    for j in -1..=1 {
-                        if i == 0 && j == 0 {
-                            continue;
-                        }
+    #[test]
+    fn part_numbers_have_spans() {
+        let schematic = Schematic::from_str(EXAMPLE).unwrap();
+
+        assert_eq!(schematic.parts.len(), 10);
+        assert_eq!(
+            schematic.parts[1],
+            PartNumber { value: BigInt::from(114u64), row: 0, col_start: 5, col_end: 8 },
+        );
+        assert_eq!(schematic.part_at(7, 0), Some(1));
+        assert_eq!(schematic.part_at(8, 0), None);
+    }
 
-                        get_part_number_at(set, &schematic, x + i, y + j)
-                            .unwrap_or(0);
-                    }
-                }
-            },
-            _ => continue,
-        }
+    #[test]
+    fn adjacency_returns_each_part_once() {
+        let schematic = Schematic::from_str("123.\n.*..\n4567").unwrap();
+
+        assert_eq!(schematic.parts_adjacent_to(1, 1), vec![0, 1]);
+        assert_eq!(schematic.parts_adjacent_to(2, 1), vec![0, 1]);
+        assert_eq!(schematic.parts_adjacent_to(3, 0), vec![0]);
     }
 
+    #[test]
+    fn numbers_longer_than_u64() {
+        let schematic = Schematic::from_str("123456789012345678901234567890*99999999999999999999").unwrap();
+
+        assert_eq!(part1(&schematic), "123456789112345678901234567889");
+        assert_eq!(
+            part2(&schematic, &GearRule::default()),
+            "12345678901234567889999999999987654321098765432110",
+        );
+    }
 
-    let ans = map
-        .iter()
-        .filter(|(_, value)| value.len() == 2)
-        .map(|(_, value)| value.iter().fold(1u64, |a, (_, num)| a * num))
-        .fold(0u64, |a, x| a + x);
+    #[test]
+    fn signed_numbers() {
+        let input = "-12*5..\n.....-3\n--7.8-9";
+
+        let schematic = Schematic::from_str(input).unwrap();
+        assert_eq!(schematic.parts.len(), 6);
+        assert_eq!(part1(&schematic), (12 + 5 + 3 + 7 + 8 + 9).to_string());
+
+        let options = SchematicOptions { signed_numbers: true, ..SchematicOptions::default() };
+        let schematic = Schematic::parse(input, &options).unwrap();
+
+        assert_eq!(
+            schematic.parts[0],
+            PartNumber { value: BigInt::from(-12i64), row: 0, col_start: 0, col_end: 3 },
+        );
+        assert_eq!(schematic.grid[(0, 2)], SchematicElement::Symbol("-".into()));
+        // `8-9` is 8 followed by -9
+        assert_eq!(part1(&schematic), (-12 + 5 - 7).to_string());
+        assert_eq!(part2(&schematic, &GearRule::default()), (-12 * 5).to_string());
+    }
 
+    #[test]
+    fn neighborhoods() {
+        let input = "1.2.3\n.....\n4.*.5\n...6.\n7.8.9";
+        let parse = |neighborhood| {
+            let options = SchematicOptions { neighborhood, ..SchematicOptions::default() };
 
-    ans.to_string()
-}
+            Schematic::parse(input, &options).unwrap()
+        };
 
+        let schematic = parse(Neighborhood::VonNeumann);
+        assert_eq!(part1(&schematic), "0");
+
+        let schematic = parse(Neighborhood::Moore);
+        assert_eq!(part1(&schematic), "6");
+
+        let schematic = parse(Neighborhood::Manhattan(2));
+        assert_eq!(part1(&schematic), (2 + 4 + 5 + 6 + 8).to_string());
+
+        let schematic = parse(Neighborhood::Chebyshev(2));
+        assert_eq!(part1(&schematic), (1..=9).sum::<u64>().to_string());
+        assert_eq!(part2(&schematic, &GearRule::default()), "0");
+    }
+
+    #[test]
+    fn unicode_symbols() {
+        // The gear is an asterisk with a variation selector, two code points in one column
+        let input = "467\u{2733}\u{FE0F}...€\n...35..7\n\u{e9}......1";
+        let schematic = Schematic::from_str(input).unwrap();
+
+        assert_eq!(schematic.grid.width(), 8);
+        assert_eq!(schematic.grid[(3, 0)], SchematicElement::Symbol("\u{2733}\u{FE0F}".into()));
+        assert_eq!(schematic.grid[(7, 0)], SchematicElement::Symbol("€".into()));
+        assert_eq!(schematic.grid[(0, 2)], SchematicElement::Symbol("\u{e9}".into()));
+        assert_eq!(
+            schematic.parts[1],
+            PartNumber { value: BigInt::from(35u64), row: 1, col_start: 3, col_end: 5 },
+        );
+        assert_eq!(part1(&schematic), (467 + 35 + 7).to_string());
+
+        let rule = GearRule { symbols: vec!["\u{2733}\u{FE0F}".to_owned()], ..GearRule::default() };
+        assert_eq!(part2(&schematic, &rule), (467 * 35).to_string());
+    }
+
+    #[test]
+    fn custom_empty_cells() {
+        let options = SchematicOptions {
+            empty: vec![".".to_owned(), "·".to_owned(), " ".to_owned()],
+            ..SchematicOptions::default()
+        };
+        let schematic = Schematic::parse("12· \n ·*.\n3.·4", &options).unwrap();
+
+        assert_eq!(schematic.grid.width(), 4);
+        assert_eq!(schematic.grid[(1, 1)], SchematicElement::Empty);
+        assert_eq!(part1(&schematic), (12 + 4).to_string());
+        assert_eq!(part2(&schematic, &GearRule::default()), (12 * 4).to_string());
+
+        // Without them the middle dot and the space are symbols
+        let schematic = Schematic::from_str("12· \n ·*.\n3.·4").unwrap();
+        assert_eq!(part1(&schematic), (12 + 3 + 4).to_string());
+
+        // Whitespace around the schematic is trimmed when it isn't a cell
+        for input in ["12.\n..*\n5.. ", "  \n12.\n..*\n5..\n   \n"] {
+            let schematic = Schematic::from_str(input).unwrap();
+
+            assert_eq!(schematic.grid.width(), 3);
+            assert_eq!(schematic.grid.height(), 3);
+            assert_eq!(part1(&schematic), "12");
+        }
+
+        // Spaces at the edges of a row are cells too, not padding to trim
+        let options = SchematicOptions { empty: vec![".".to_owned(), " ".to_owned()], ..SchematicOptions::default() };
+        let schematic = Schematic::parse("\n 12\n.*.\n34 \n\n", &options).unwrap();
+
+        assert_eq!(schematic.grid.width(), 3);
+        assert_eq!(part1(&schematic), (12 + 34).to_string());
+    }
+}
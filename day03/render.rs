@@ -0,0 +1,188 @@
+use std::collections::HashSet;
+
+use crate::{gear::GearRule, graph::AdjacencyGraph, Schematic, SchematicElement};
+
+const RESET: &str = "\x1b[0m";
+const COUNTED: &str = "\x1b[32m";
+const UNCOUNTED: &str = "\x1b[31m";
+const SYMBOL: &str = "\x1b[33m";
+const GEAR: &str = "\x1b[1;35m";
+const GEAR_PART: &str = "\x1b[1;36m";
+
+/**
+ * Square of cells around (x, y), clipped to the schematic
+ */
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Window {
+    pub x: usize,
+    pub y: usize,
+    pub radius: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Style {
+    Plain,
+    Counted,
+    Uncounted,
+    Symbol,
+    Gear,
+    GearPart,
+}
+
+impl Style {
+    fn code(&self) -> &'static str {
+        match self {
+            Style::Plain => RESET,
+            Style::Counted => COUNTED,
+            Style::Uncounted => UNCOUNTED,
+            Style::Symbol => SYMBOL,
+            Style::Gear => GEAR,
+            Style::GearPart => GEAR_PART,
+        }
+    }
+}
+
+/**
+ * Renders the schematic with ANSI colors: green parts are counted by part 1,
+ * red ones are not, yellow cells are symbols, and gears (as defined by `rule`)
+ * are magenta with their parts in cyan.
+ */
+pub fn render(schematic: &Schematic, rule: &GearRule, window: Option<Window>) -> String {
+    let graph = AdjacencyGraph::new(schematic);
+    let mut part_styles = vec![Style::Counted; schematic.parts.len()];
+    let mut gears: HashSet<(usize, usize)> = HashSet::new();
+
+    for part in graph.isolated_parts() {
+        part_styles[part] = Style::Uncounted;
+    }
+
+    for gear in rule.gears(&graph) {
+        let node = &graph.symbols()[gear];
+        gears.insert((node.x, node.y));
+
+        for part in graph.parts_of(gear) {
+            part_styles[*part] = Style::GearPart;
+        }
+    }
+
+    let (width, height) = (schematic.grid.width(), schematic.grid.height());
+    let (xs, ys) = match window {
+        Some(Window { x, y, radius }) => (
+            x.saturating_sub(radius)..(x + radius + 1).min(width),
+            y.saturating_sub(radius)..(y + radius + 1).min(height),
+        ),
+        None => (0..width, 0..height),
+    };
+
+    let mut out = String::new();
+
+    for y in ys {
+        let mut current = Style::Plain;
+
+        for x in xs.clone() {
+            let (style, c) = match &schematic.grid[(x, y)] {
+                SchematicElement::Empty => (Style::Plain, schematic.empty.clone()),
+                SchematicElement::Part(i) => {
+                    let part = &schematic.parts[*i];
+                    // Zero padded back to its width so leading zeros survive
+                    let digit = format!("{:0width$}", part.value, width = part.col_end - part.col_start)
+                        .chars()
+                        .nth(x - part.col_start)
+                        .unwrap_or('?');
+
+                    (part_styles[*i], digit.to_string())
+                },
+                SchematicElement::Symbol(c) if gears.contains(&(x, y)) => (Style::Gear, c.to_string()),
+                SchematicElement::Symbol(c) => (Style::Symbol, c.to_string()),
+            };
+
+            if style != current {
+                out.push_str(style.code());
+                current = style;
+            }
+
+            out.push_str(&c);
+        }
+
+        if current != Style::Plain {
+            out.push_str(RESET);
+        }
+
+        out.push('\n');
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use crate::{gear::{GearRule, PartCount}, render::{render, Window}, Schematic, SchematicOptions};
+
+    #[test]
+    fn colors_every_kind_of_cell() {
+        let schematic = Schematic::from_str("12.3\n.*.#\n45..").unwrap();
+
+        assert_eq!(
+            render(&schematic, &GearRule::default(), None),
+            "\x1b[1;36m12\x1b[0m.\x1b[32m3\x1b[0m\n\
+            .\x1b[1;35m*\x1b[0m.\x1b[33m#\x1b[0m\n\
+            \x1b[1;36m45\x1b[0m..\n",
+        );
+    }
+
+    #[test]
+    fn uncounted_parts() {
+        let schematic = Schematic::from_str("12..\n...#\n..45").unwrap();
+
+        assert_eq!(
+            render(&schematic, &GearRule::default(), None),
+            "\x1b[31m12\x1b[0m..\n\
+            ...\x1b[33m#\x1b[0m\n\
+            ..\x1b[32m45\x1b[0m\n",
+        );
+    }
+
+    #[test]
+    fn keeps_leading_zeros_and_signs() {
+        let options = SchematicOptions { signed_numbers: true, ..SchematicOptions::default() };
+        let schematic = Schematic::parse("-007#", &options).unwrap();
+
+        assert_eq!(
+            render(&schematic, &GearRule::default(), None),
+            "\x1b[32m-007\x1b[33m#\x1b[0m\n",
+        );
+    }
+
+    #[test]
+    fn unicode_symbols_and_empty_cells() {
+        let options = SchematicOptions { empty: vec!["·".to_owned()], ..SchematicOptions::default() };
+        let schematic = Schematic::parse("12·\n·\u{2733}\u{FE0F}€", &options).unwrap();
+        let rule = GearRule {
+            symbols: vec!["\u{2733}\u{FE0F}".to_owned()],
+            parts: PartCount::exactly(1),
+            ..GearRule::default()
+        };
+
+        assert_eq!(
+            render(&schematic, &GearRule::default(), None),
+            "\x1b[32m12\x1b[0m·\n·\x1b[33m\u{2733}\u{FE0F}€\x1b[0m\n",
+        );
+        assert_eq!(
+            render(&schematic, &rule, None),
+            "\x1b[1;36m12\x1b[0m·\n·\x1b[1;35m\u{2733}\u{FE0F}\x1b[33m€\x1b[0m\n",
+        );
+    }
+
+    #[test]
+    fn crops_to_window() {
+        let schematic = Schematic::from_str(include_str!("exampleinput")).unwrap();
+        let window = Window { x: 6, y: 1, radius: 1 };
+
+        assert_eq!(
+            render(&schematic, &GearRule::default(), Some(window)),
+            "\x1b[31m114\x1b[0m\n...\n.\x1b[32m63\x1b[0m\n",
+        );
+    }
+}
@@ -0,0 +1,313 @@
+use std::{collections::VecDeque, io::BufRead, iter, ops::AddAssign};
+
+use aoc2023::bigint::BigInt;
+
+use crate::{gear::GearRule, is_padding, parse_row, PartNumber, RaggedRows, SchematicElement, SchematicOptions};
+
+static EMPTY: SchematicElement = SchematicElement::Empty;
+
+#[derive(Debug, Default)]
+struct Row {
+    elements: Vec<SchematicElement>,
+    /**
+     * Parts of this row only, `SchematicElement::Part` indexes into this
+     */
+    parts: Vec<PartNumber>,
+}
+
+impl Row {
+    fn parse(line: &str, row: usize, options: &SchematicOptions) -> Self {
+        let mut parts = vec![];
+        let elements = parse_row(line, row, options, &mut parts);
+
+        Self { elements, parts }
+    }
+
+    /**
+     * Cells past either end of the row are empty, which is the same as
+     * padding every row to the widest one
+     */
+    fn get(&self, x: i64) -> &SchematicElement {
+        if x < 0 {
+            return &EMPTY;
+        }
+
+        self.elements
+            .get(x as usize)
+            .unwrap_or(&EMPTY)
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Totals {
+    pub part_sum: BigInt,
+    pub gear_sum: BigInt,
+}
+
+impl AddAssign for Totals {
+    fn add_assign(&mut self, other: Self) {
+        self.part_sum += other.part_sum;
+        self.gear_sum += other.gear_sum;
+    }
+}
+
+/**
+ * Solves both parts while only keeping the rows a symbol can reach in memory,
+ * three rows for the default neighbourhood. Gives the same answers as `part1`
+ * and `part2`.
+ */
+pub fn solve<R: BufRead>(reader: R, options: &SchematicOptions, rule: &GearRule) -> Result<Totals, String> {
+    let radius = options.neighborhood.radius();
+    let offsets = options.neighborhood.offsets();
+    let mut totals = Totals::default();
+    // Rows above the schematic are empty, the current row is in the middle
+    let mut window: VecDeque<Row> = (0..radius).map(|_| Row::default()).collect();
+    let mut width: Option<usize> = None;
+    let mut row = 0;
+
+    let mut push_row = |line: &str| -> Result<(), String> {
+        let parsed = Row::parse(line, row, options);
+        let row_width = parsed.elements.len();
+        let expected = *width.get_or_insert(row_width);
+
+        if options.ragged_rows == RaggedRows::Reject && row_width != expected {
+            return Err(
+                format!("Row {} has a width of {}, expected {}", row + 1, row_width, expected)
+            );
+        }
+
+        window.push_back(parsed);
+
+        if window.len() == 2 * radius + 1 {
+            totals += solve_row(&window, radius, &offsets, rule);
+            window.pop_front();
+        }
+
+        row += 1;
+
+        Ok(())
+    };
+
+    // The input is trimmed the same way `Schematic::parse` does it: lines of
+    // padding only count as rows if something comes after them, and the last
+    // line with cells is held back until it's known whether its end gets
+    // trimmed
+    let is_padding = |c: char| is_padding(c, options);
+    let mut blank_lines: Vec<String> = vec![];
+    let mut last: Option<String> = None;
+
+    for (i, line) in reader.lines().enumerate() {
+        let line = line.map_err(|err| format!("Failed to read line {}: {}", i + 1, err))?;
+
+        if line.chars().all(is_padding) {
+            if last.is_some() {
+                blank_lines.push(line);
+            }
+
+            continue;
+        }
+
+        match last.take() {
+            None => last = Some(line.trim_start_matches(is_padding).to_owned()),
+            Some(previous) => {
+                for line in iter::once(&previous).chain(blank_lines.iter()) {
+                    push_row(line)?;
+                }
+
+                blank_lines.clear();
+                last = Some(line);
+            },
+        }
+    }
+
+    if let Some(line) = last {
+        push_row(line.trim_end_matches(is_padding))?;
+    }
+
+    if width.is_none() {
+        return Err("Should have at least one element".to_owned());
+    }
+
+    // And so are the rows below it
+    for _ in 0..radius {
+        window.push_back(Row::default());
+
+        if window.len() == 2 * radius + 1 {
+            totals += solve_row(&window, radius, &offsets, rule);
+            window.pop_front();
+        }
+    }
+
+    Ok(totals)
+}
+
+/**
+ * Totals for the parts and gears of the row in the middle of the window
+ */
+fn solve_row(window: &VecDeque<Row>, radius: usize, offsets: &[(i64, i64)], rule: &GearRule) -> Totals {
+    let current = &window[radius];
+    let cell = |x: i64, dx: i64, dy: i64| {
+        let row = &window[(radius as i64 + dy) as usize];
+
+        (row, row.get(x + dx))
+    };
+
+    let mut totals = Totals::default();
+
+    for part in current.parts.iter() {
+        let touches_symbol = (part.col_start..part.col_end)
+            .flat_map(|x| offsets.iter().map(move |(dx, dy)| (x as i64, *dx, *dy)))
+            .any(|(x, dx, dy)| matches!(cell(x, dx, dy).1, SchematicElement::Symbol(_)));
+
+        if touches_symbol {
+            totals.part_sum += &part.value;
+        }
+    }
+
+    for (x, element) in current.elements.iter().enumerate() {
+        let SchematicElement::Symbol(symbol) = element else {
+            continue;
+        };
+
+        if ! rule.is_gear_symbol(symbol) {
+            continue;
+        }
+
+        // Offsets are row-major, so the cells of a part come out next to each other
+        let mut last = None;
+        let mut values: Vec<&BigInt> = vec![];
+
+        for (dx, dy) in offsets {
+            match cell(x as i64, *dx, *dy) {
+                (row, SchematicElement::Part(i)) if last != Some((*dy, *i)) => {
+                    values.push(&row.parts[*i].value);
+                    last = Some((*dy, *i));
+                },
+                _ => continue,
+            }
+        }
+
+        if rule.parts.contains(values.len()) {
+            totals.gear_sum += rule.combine.apply(values.into_iter());
+        }
+    }
+
+    totals
+}
+
+#[cfg(test)]
+mod tests {
+    use aoc2023::{grid::Neighborhood, rng::Rng};
+
+    use crate::{
+        gear::{Combine, GearRule, PartCount},
+        part1,
+        part2,
+        stream::solve,
+        RaggedRows,
+        Schematic,
+        SchematicOptions,
+    };
+
+    const EXAMPLE: &str = include_str!("exampleinput");
+
+    fn random_schematic(rng: &mut Rng, width: u64, height: u64) -> String {
+        let symbols = ["*", "#", "+", "$", "/", "@", "-", "€", "\u{2733}\u{FE0F}"];
+        let digits = ["0", "1", "2", "3", "4", "5", "6", "7", "8", "9"];
+
+        (0..height)
+            .map(|_| {
+                (0..width)
+                    .map(|_| match rng.below(10) {
+                        0..=3 => ".",
+                        4..=7 => *rng.pick(&digits),
+                        _ => *rng.pick(&symbols),
+                    })
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    #[test]
+    fn example() {
+        let totals = solve(EXAMPLE.as_bytes(), &SchematicOptions::default(), &GearRule::default()).unwrap();
+
+        assert_eq!(totals.part_sum.to_string(), "4361");
+        assert_eq!(totals.gear_sum.to_string(), "467835");
+    }
+
+    #[test]
+    fn ragged_rows() {
+        let err = solve("467..\n...*\n..35.".as_bytes(), &SchematicOptions::default(), &GearRule::default())
+            .unwrap_err();
+
+        assert_eq!(err, "Row 2 has a width of 4, expected 5");
+
+        let options = SchematicOptions { ragged_rows: RaggedRows::Pad, ..SchematicOptions::default() };
+        let totals = solve("467\n...*..\n..35\n\n".as_bytes(), &options, &GearRule::default()).unwrap();
+
+        assert_eq!(totals.part_sum.to_string(), (467 + 35).to_string());
+        assert_eq!(totals.gear_sum.to_string(), (467 * 35).to_string());
+    }
+
+    #[test]
+    fn whitespace_empty_cells() {
+        let options = SchematicOptions { empty: vec![".".to_owned(), " ".to_owned()], ..SchematicOptions::default() };
+        let input = " 12\n.*.\n34 \n";
+        let schematic = Schematic::parse(input, &options).unwrap();
+        let totals = solve(input.as_bytes(), &options, &GearRule::default()).unwrap();
+
+        assert_eq!(totals.part_sum.to_string(), part1(&schematic));
+        assert_eq!(totals.gear_sum.to_string(), part2(&schematic, &GearRule::default()));
+        assert_eq!(totals.gear_sum.to_string(), (12 * 34).to_string());
+
+        // Without spaces as cells they are only trimmed around the schematic
+        for input in ["12.\n..*\n5.. ", "  \n12.\n..*\n5..\n   \n", "12 \n . \n5.*"] {
+            let options = SchematicOptions::default();
+            let result = solve(input.as_bytes(), &options, &GearRule::default())
+                .map(|totals| totals.part_sum.to_string());
+
+            assert_eq!(result, Schematic::parse(input, &options).map(|schematic| part1(&schematic)), "{:?}", input);
+        }
+    }
+
+    #[test]
+    fn matches_grid_solver() {
+        let mut rng = Rng::new(3);
+        let neighborhoods = [
+            Neighborhood::Moore,
+            Neighborhood::VonNeumann,
+            Neighborhood::Manhattan(2),
+            Neighborhood::Chebyshev(3),
+            Neighborhood::Chebyshev(0),
+        ];
+        let rules = [
+            GearRule::default(),
+            GearRule {
+                symbols: vec!["*".to_owned(), "#".to_owned(), "\u{2733}\u{FE0F}".to_owned()],
+                parts: PartCount { min: 1, max: 3 },
+                combine: Combine::Sum,
+            },
+        ];
+
+        for _ in 0..200 {
+            let (width, height) = (rng.range(1, 30), rng.range(1, 12));
+            let input = random_schematic(&mut rng, width, height);
+
+            let neighborhood = *rng.pick(&neighborhoods);
+
+            for signed_numbers in [false, true] {
+                let options = SchematicOptions { signed_numbers, neighborhood, ..SchematicOptions::default() };
+                let schematic = Schematic::parse(&input, &options).unwrap();
+
+                for rule in rules.iter() {
+                    let totals = solve(input.as_bytes(), &options, rule).unwrap();
+
+                    assert_eq!(totals.part_sum.to_string(), part1(&schematic), "{}", input);
+                    assert_eq!(totals.gear_sum.to_string(), part2(&schematic, rule), "{}", input);
+                }
+            }
+        }
+    }
+}
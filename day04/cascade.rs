@@ -0,0 +1,156 @@
+use aoc2023::bigint::BigInt;
+
+use crate::Card;
+
+/**
+ * How a single card ended up with the copies it holds
+ */
+#[derive(Debug, Clone, PartialEq)]
+pub struct CascadeRow {
+    pub id: usize,
+    pub matches: usize,
+    /**
+     * Including the original card
+     */
+    pub copies: BigInt,
+    /**
+     * Earlier cards that won copies of this one, with how many each of them won
+     */
+    pub sources: Vec<(usize, BigInt)>,
+}
+
+impl CascadeRow {
+    /**
+     * The earlier card that won the most copies of this one, the first one on
+     * a tie. `None` for cards that only hold their original.
+     */
+    pub fn top_source(&self) -> Option<usize> {
+        self.sources
+            .iter()
+            .rev()
+            .max_by(|(_, a), (_, b)| a.cmp(b))
+            .map(|(id, _)| *id)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cascade {
+    pub rows: Vec<CascadeRow>,
+    pub total_copies: BigInt,
+}
+
+/**
+ * Plays out the copy cascade of part 2, keeping track of where every copy
+ * came from. `cards` have to be ordered by id without gaps, as returned by
+ * `parse_cards`.
+ */
+pub fn cascade(cards: &[Card]) -> Cascade {
+    let mut rows: Vec<CascadeRow> = cards
+        .iter()
+        .map(|card| CascadeRow { id: card.id, matches: card.matches, copies: BigInt::from(1u64), sources: vec![] })
+        .collect();
+
+    for i in 0..rows.len() {
+        let (id, matches, copies) = (rows[i].id, rows[i].matches, rows[i].copies.clone());
+
+        for row in rows.iter_mut().skip(i + 1).take(matches) {
+            row.copies += &copies;
+            row.sources.push((id, copies.clone()));
+        }
+    }
+
+    let total_copies = rows.iter().map(|row| &row.copies).sum();
+
+    Cascade { rows, total_copies }
+}
+
+impl Cascade {
+    /**
+     * One line per card with aligned columns, followed by the total
+     */
+    pub fn to_table(&self) -> String {
+        let headers = ["Card", "Matches", "Copies", "Top source", "Won from"];
+        let mut lines: Vec<[String; 5]> = vec![headers.map(String::from)];
+
+        for row in self.rows.iter() {
+            let won_from = row.sources
+                .iter()
+                .map(|(id, copies)| format!("{} ({})", id, copies))
+                .collect::<Vec<_>>()
+                .join(", ");
+
+            lines.push([
+                row.id.to_string(),
+                row.matches.to_string(),
+                row.copies.to_string(),
+                row.top_source().map(|id| id.to_string()).unwrap_or_else(|| "-".to_owned()),
+                won_from,
+            ]);
+        }
+
+        let widths: Vec<usize> = (0..headers.len())
+            .map(|col| lines.iter().map(|line| line[col].len()).max().unwrap_or(0))
+            .collect();
+
+        let mut out = String::new();
+
+        for line in lines.iter() {
+            let cells: Vec<String> = line
+                .iter()
+                .zip(widths.iter())
+                .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+                .collect();
+
+            out.push_str(cells.join(" | ").trim_end());
+            out.push('\n');
+        }
+
+        out.push_str(&format!("Total copies: {}\n", self.total_copies));
+
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use aoc2023::bigint::BigInt;
+
+    use crate::{cascade::cascade, parse_cards, tests::card};
+
+    #[test]
+    fn example() {
+        let cards = parse_cards(include_str!("exampleinput")).unwrap();
+        let cascade = cascade(&cards);
+        let copies = cascade.rows.iter().map(|row| row.copies.to_string()).collect::<Vec<_>>();
+        let sources = cascade.rows[4].sources.iter().map(|(id, n)| (*id, n.to_string())).collect::<Vec<_>>();
+
+        assert_eq!(cascade.total_copies.to_string(), "30");
+        assert_eq!(copies, ["1", "2", "4", "8", "14", "1"]);
+        assert_eq!(sources, [(1, "1".to_owned()), (3, "4".to_owned()), (4, "8".to_owned())]);
+        assert_eq!(cascade.rows[4].top_source(), Some(4));
+        assert_eq!(cascade.rows[0].top_source(), None);
+    }
+
+    #[test]
+    fn table() {
+        let cards = parse_cards("Card 1: 1 2 | 1 2\nCard 2: 3 | 3\nCard 3: 4 | 5").unwrap();
+
+        assert_eq!(
+            cascade(&cards).to_table(),
+            "Card | Matches | Copies | Top source | Won from\n\
+            1    | 2       | 1      | -          |\n\
+            2    | 1       | 2      | 1          | 1 (1)\n\
+            3    | 0       | 4      | 2          | 1 (1), 2 (2)\n\
+            Total copies: 7\n",
+        );
+    }
+
+    #[test]
+    fn copies_beyond_u64() {
+        let cards = (1..=80).map(|id| card(id, 80)).collect::<Vec<_>>();
+        let cascade = cascade(&cards);
+
+        assert_eq!(cascade.rows[79].copies, BigInt::from(2u64).pow(79));
+        assert_eq!(cascade.rows[79].top_source(), Some(79));
+    }
+}
@@ -0,0 +1,168 @@
+use std::{collections::HashMap, fmt, str::FromStr};
+
+use crate::Card;
+
+/**
+ * Numbers a card is expected to hold, inclusive
+ */
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LintOptions {
+    pub min: u64,
+    pub max: u64,
+}
+
+impl Default for LintOptions {
+    fn default() -> Self {
+        Self { min: 1, max: 99 }
+    }
+}
+
+/**
+ * `min-max`
+ */
+impl FromStr for LintOptions {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parse = |n: &str| n
+            .trim()
+            .parse::<u64>()
+            .map_err(|_| format!("Failed to parse lint range: {}", s));
+
+        let (min, max) = s
+            .split_once('-')
+            .ok_or_else(|| format!("Expected a lint range as min-max, got {}", s))?;
+
+        let (min, max) = (parse(min)?, parse(max)?);
+
+        if min > max {
+            return Err(format!("Lint range minimum is above its maximum: {}", s));
+        }
+
+        Ok(Self { min, max })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    /**
+     * 1 based, counting blank lines
+     */
+    pub line: usize,
+    pub text: String,
+    pub message: String,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}\n    {}", self.line, self.message, self.text)
+    }
+}
+
+/**
+ * Problems that don't stop the input from parsing but probably make the
+ * answers wrong: duplicate numbers on either side of a card (each duplicate
+ * I have counts as another match), cards with a different amount of numbers
+ * than the first one, and numbers outside of the expected range.
+ */
+pub fn lint(input: &str, options: &LintOptions) -> Vec<Diagnostic> {
+    let mut diagnostics = vec![];
+    // Winning and own list lengths of the first card, and the line it is on
+    let mut expected: Option<(usize, usize, usize)> = None;
+
+    for (i, text) in input.lines().enumerate() {
+        if text.trim().is_empty() {
+            continue;
+        }
+
+        let mut report = |message: String| diagnostics.push(
+            Diagnostic { line: i + 1, text: text.to_owned(), message }
+        );
+
+        let card = match Card::from_str(text) {
+            Err(err) => {
+                report(err);
+                continue;
+            },
+            Ok(v) => v,
+        };
+
+        for (side, nums) in [("Winning", &card.winning_nums), ("Own", &card.my_nums)] {
+            let mut counts: HashMap<u64, usize> = HashMap::new();
+
+            for num in nums.iter() {
+                *counts.entry(*num).or_insert(0) += 1;
+            }
+
+            let mut duplicates = counts.into_iter().filter(|(_, count)| *count > 1).collect::<Vec<_>>();
+            duplicates.sort();
+
+            for (num, count) in duplicates {
+                report(format!("{} number {} is listed {} times", side, num, count));
+            }
+
+            for num in nums.iter().filter(|num| *num < &options.min || *num > &options.max) {
+                report(
+                    format!("{} number {} is outside of {}-{}", side, num, options.min, options.max)
+                );
+            }
+        }
+
+        let sizes = (card.winning_nums.len(), card.my_nums.len());
+
+        match expected {
+            None => expected = Some((sizes.0, sizes.1, i + 1)),
+            Some((winning, own, line)) if (winning, own) != sizes => report(
+                format!(
+                    "Has {} winning and {} own numbers, expected {} and {} like line {}",
+                    sizes.0, sizes.1, winning, own, line,
+                )
+            ),
+            Some(_) => (),
+        }
+    }
+
+    diagnostics
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use crate::lint::{lint, LintOptions};
+
+    #[test]
+    fn clean_example() {
+        assert!(lint(include_str!("exampleinput"), &LintOptions::default()).is_empty());
+    }
+
+    #[test]
+    fn finds_problems() {
+        let input = "Card 1: 1 2 3 | 4 5 6\n\nCard 2: 1 2 2 | 4 4 4\nCard 3: 1 2 | 3 100 0\nCard x: 1 | 2";
+        let messages = lint(input, &LintOptions::default())
+            .into_iter()
+            .map(|diagnostic| (diagnostic.line, diagnostic.message))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            messages,
+            vec![
+                (3, "Winning number 2 is listed 2 times".to_owned()),
+                (3, "Own number 4 is listed 3 times".to_owned()),
+                (4, "Own number 100 is outside of 1-99".to_owned()),
+                (4, "Own number 0 is outside of 1-99".to_owned()),
+                (4, "Has 2 winning and 3 own numbers, expected 3 and 3 like line 1".to_owned()),
+                (5, "Failed to parse card id: x".to_owned()),
+            ],
+        );
+    }
+
+    #[test]
+    fn display_and_options() {
+        let options = LintOptions::from_str("0-100").unwrap();
+        let diagnostics = lint("Card 1: 1 | 101", &options);
+
+        assert_eq!(diagnostics[0].to_string(), "line 1: Own number 101 is outside of 0-100\n    Card 1: 1 | 101");
+        assert!(LintOptions::from_str("5-1").is_err());
+    }
+}
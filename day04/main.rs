@@ -1,18 +1,62 @@
-use std::{fs, str::FromStr, collections::{HashSet, HashMap}};
+use std::{env, str::FromStr};
+
+use aoc2023::{bigint::BigInt, input::{arg_value, read_input}, trace};
+use cascade::cascade;
+use lint::{lint, LintOptions};
+use matching::WinningSet;
+use scoring::{parse_scoring, Doubling, Scoring};
+
+mod cascade;
+mod lint;
+mod matching;
+mod scoring;
 
 fn main() {
-    let contents = fs::read_to_string("./input")
-        .expect("File not found");
+    let contents = read_input();
+
+    if env::args().any(|arg| arg == "--lint") {
+        let options = match arg_value("--lint-range") {
+            None => LintOptions::default(),
+            Some(s) => LintOptions::from_str(&s).unwrap_or_else(|err| panic!("{}", err)),
+        };
+
+        let diagnostics = lint(&contents, &options);
+
+        for diagnostic in diagnostics.iter() {
+            println!("{}", diagnostic);
+        }
+
+        println!("{} problem(s) found", diagnostics.len());
+        return;
+    }
+
+    let cards = match parse_cards(&contents) {
+        Err(err) => panic!("{}", err),
+        Ok(v) => v,
+    };
 
+    let scoring: Box<dyn Scoring> = match arg_value("--scoring") {
+        None => Box::new(Doubling),
+        Some(s) => parse_scoring(&s).unwrap_or_else(|err| panic!("{}", err)),
+    };
 
-    println!("Part 1: {}", part1(&contents));
-    println!("Part 2: {}", part2(&contents));
+    if env::args().any(|arg| arg == "--report") {
+        print!("{}", cascade(&cards).to_table());
+    }
+
+    println!("Part 1: {}", part1(&cards, scoring.as_ref()));
+    println!("Part 2: {}", part2(&cards));
 }
 
 #[derive(Debug)]
 struct Card {
+    id: usize,
     winning_nums: Vec<u64>,
     my_nums: Vec<u64>,
+    /**
+     * How many of my numbers are winning numbers
+     */
+    matches: usize,
 }
 
 impl FromStr for Card {
@@ -21,106 +65,232 @@ impl FromStr for Card {
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let trimmed = s.trim();
         
-        let (_id_str, rest) = trimmed
+        let (id_str, rest) = trimmed
             .strip_prefix("Card")
             .and_then(|x| x.trim().split_once(':'))
             .ok_or("Failed to parse")?;
 
+        let id = id_str
+            .parse()
+            .map_err(|_| format!("Failed to parse card id: {}", id_str))?;
+
         let (winning_nums_str, my_nums_str) = rest
             .split_once('|')
             .ok_or("Failed to parse")?;
 
 
         let winning_nums = winning_nums_str
-            .trim()
             .split_whitespace()
             .map(|x| x.parse().ok())
             .collect::<Option<Vec<u64>>>()
             .ok_or("Failed to parse")?;
 
         let my_nums = my_nums_str
-            .trim()
             .split_whitespace()
             .map(|x| x.parse().ok())
             .collect::<Option<Vec<u64>>>()
             .ok_or("Failed to parse")?;
 
+        let matches = WinningSet::new(&winning_nums).count_matches(&my_nums);
+
         Ok(
             Self {
+                id,
                 winning_nums,
                 my_nums,
+                matches,
             }
         )
     }
 }
 
-fn part1(input: &str) -> String {
-    let cards = input
+/**
+ * Parses every card and orders them by id. Cards can be listed in any order,
+ * but the ids have to go from 1 up without gaps or duplicates since a card
+ * wins copies of the cards with the ids right after its own.
+ */
+fn parse_cards(input: &str) -> Result<Vec<Card>, String> {
+    let mut cards = input
         .trim()
         .lines()
-        .map(|x| Card::from_str(x))
-        .collect::<Result<Vec<Card>, <Card as FromStr>::Err>>()
-        .expect("Failed to parse");
+        .map(Card::from_str)
+        .collect::<Result<Vec<Card>, <Card as FromStr>::Err>>()?;
+
+    cards.sort_by_key(|card| card.id);
+
+    let missing = |from: usize, to: usize| {
+        if from == to {
+            format!("Card {} is missing", from)
+        } else {
+            format!("Cards {} to {} are missing", from, to)
+        }
+    };
+
+    match cards.first() {
+        None => return Err("Should have at least one card".to_owned()),
+        Some(card) if card.id == 0 => return Err("Card ids start at 1, found card 0".to_owned()),
+        Some(card) if card.id != 1 => return Err(missing(1, card.id - 1)),
+        Some(_) => (),
+    }
+
+    for pair in cards.windows(2) {
+        if pair[0].id == pair[1].id {
+            return Err(format!("Card {} is listed more than once", pair[0].id));
+        }
 
-    let ans = cards
+        if pair[1].id != pair[0].id + 1 {
+            return Err(missing(pair[0].id + 1, pair[1].id - 1));
+        }
+    }
+
+    Ok(cards)
+}
+
+fn part1(cards: &[Card], scoring: &dyn Scoring) -> String {
+    let ans: BigInt = cards
         .iter()
-        .fold(0u64, |a, c| {
-            let set: HashSet<u64> = HashSet::from_iter(c.winning_nums.iter().cloned());
-            let winning_nums_count = c.my_nums
-                .iter()
-                .filter(|num| set.contains(num))
-                .collect::<Vec<&u64>>()
-                .len();
-            
-            let points = {
-                if winning_nums_count == 0 {
-                    0
-                } else {
-                    2u64.pow(winning_nums_count as u32 - 1)
-                }
-            };
-
-            a + points
-        });
-    
+        .map(|c| scoring.points(c.matches))
+        .sum();
 
     ans.to_string()
 }
 
-fn part2(input: &str) -> String {
-    let cards = input
-        .trim()
-        .lines()
-        .map(|x| Card::from_str(x))
-        .collect::<Result<Vec<Card>, <Card as FromStr>::Err>>()
-        .expect("Failed to parse");
+/**
+ * Every card adds its copies to a run of the cards after it, so instead of
+ * adding them one by one the run is marked at both ends in a difference array
+ * and the marks are summed up while walking the cards. Linear in the number
+ * of cards no matter how many matches they have.
+ */
+fn part2(cards: &[Card]) -> String {
+    // Copies won by earlier cards start at `diff[i]` and stop before `diff[j]`
+    let mut diff: Vec<BigInt> = vec![BigInt::zero(); cards.len() + 1];
+    let mut won = BigInt::zero();
+    let mut total_copies = BigInt::zero();
+
+    for (i, card) in cards.iter().enumerate() {
+        won += &diff[i];
 
-    type Copies = u64;
+        let copies = &won + &BigInt::from(1u64);
+        let end = (i + 1 + card.matches).min(cards.len());
 
-    let mut map: HashMap<usize, Copies> = HashMap::new();
+        if end > i + 1 {
+            diff[i + 1] += &copies;
+            diff[end] = &diff[end] - &copies;
+        }
 
-    let mut total_copies: u64 = 0;
+        trace!("Card {} has {} match(es) and {} copies", card.id, card.matches, copies);
 
-    for i in 0..cards.len() {
-        let card = &cards[i];
+        total_copies += copies;
+    }
 
-        let winning_nums_count = card.my_nums
-            .iter()
-            .filter(|num| card.winning_nums.contains(num))
-            .collect::<Vec<&u64>>()
-            .len();
+    total_copies.to_string()
+}
 
-        let copies = *map.get(&i).unwrap_or(&1);
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
 
-        total_copies += copies;
+    use aoc2023::rng::Rng;
 
-        for j in (i + 1)..((i + 1 + winning_nums_count).min(cards.len())) {
-            let child_copies = map.entry(j).or_insert(1);
+    use crate::{parse_cards, part1, part2, scoring::{Doubling, Linear}, Card};
 
-            *child_copies += copies;
+    const EXAMPLE: &str = include_str!("exampleinput");
+
+    #[test]
+    fn example() {
+        let cards = parse_cards(EXAMPLE).unwrap();
+
+        assert_eq!(cards.iter().map(|card| card.matches).collect::<Vec<_>>(), vec![4, 2, 2, 1, 0, 0]);
+        assert_eq!(part1(&cards, &Doubling), "13");
+        assert_eq!(part1(&cards, &Linear), "9");
+        assert_eq!(part2(&cards), "30");
+    }
+
+    #[test]
+    fn scores_beyond_u64() {
+        let nums = (1..=70).map(|n| n.to_string()).collect::<Vec<_>>().join(" ");
+        let cards = parse_cards(&format!("Card 1: {} | {}\nCard 2: 1 | 1", nums, nums)).unwrap();
+
+        assert_eq!(part1(&cards, &Doubling), "590295810358705651713");
+    }
+
+    #[test]
+    fn cards_in_any_order() {
+        let mut lines = EXAMPLE.lines().collect::<Vec<_>>();
+        lines.reverse();
+        let shuffled = lines.join("\n");
+
+        let cards = parse_cards(&shuffled).unwrap();
+        let ids = cards.iter().map(|card| card.id).collect::<Vec<_>>();
+
+        assert_eq!(ids, vec![1, 2, 3, 4, 5, 6]);
+        assert_eq!(part2(&cards), "30");
+    }
+
+    #[test]
+    fn rejects_duplicates_and_gaps() {
+        let card = |id: usize| format!("Card {}: 1 2 | 3 4", id);
+        let input = |ids: &[usize]| ids.iter().map(|id| card(*id)).collect::<Vec<_>>().join("\n");
+
+        assert_eq!(parse_cards(&input(&[1, 2, 2, 3])).unwrap_err(), "Card 2 is listed more than once");
+        assert_eq!(parse_cards(&input(&[1, 2, 5])).unwrap_err(), "Cards 3 to 4 are missing");
+        assert_eq!(parse_cards(&input(&[2, 3])).unwrap_err(), "Card 1 is missing");
+        assert_eq!(parse_cards(&input(&[0, 1])).unwrap_err(), "Card ids start at 1, found card 0");
+        assert_eq!(parse_cards("Card x: 1 | 2").unwrap_err(), "Failed to parse card id: x");
+    }
+
+    /**
+     * The original cascade, adding each card's copies to every card it wins
+     */
+    fn part2_reference(cards: &[Card]) -> String {
+        type Copies = u64;
+
+        // Keyed by card id
+        let mut map: HashMap<usize, Copies> = HashMap::new();
+
+        let mut total_copies: u64 = 0;
+
+        for card in cards.iter() {
+            let winning_nums_count = card.matches;
+
+            let copies = *map.get(&card.id).unwrap_or(&1);
+
+            total_copies += copies;
+
+            for id in (card.id + 1)..=(card.id + winning_nums_count).min(cards.len()) {
+                let child_copies = map.entry(id).or_insert(1);
+
+                *child_copies += copies;
+            }
         }
+
+        total_copies.to_string()
     }
-    
 
-    total_copies.to_string()
+    pub fn card(id: usize, matches: usize) -> Card {
+        Card { id, winning_nums: vec![], my_nums: vec![], matches }
+    }
+
+    #[test]
+    fn matches_reference_cascade() {
+        let mut rng = Rng::new(42);
+
+        for _ in 0..500 {
+            let len = rng.range(1, 40) as usize;
+            let max_matches = *rng.pick(&[0, 1, 3, 10]);
+            let cards = (1..=len)
+                .map(|id| card(id, rng.below(max_matches + 1) as usize))
+                .collect::<Vec<_>>();
+
+            assert_eq!(part2(&cards), part2_reference(&cards));
+        }
+    }
+
+    #[test]
+    fn copies_beyond_u64() {
+        // Every card wins all the cards after it, doubling the copies each time
+        let cards = (1..=80).map(|id| card(id, 80)).collect::<Vec<_>>();
+
+        assert_eq!(part2(&cards), "1208925819614629174706175");
+    }
 }
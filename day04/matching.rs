@@ -0,0 +1,158 @@
+use std::cmp::Ordering;
+
+/**
+ * Numbers below this go in a bitset, anything larger makes the whole set
+ * fall back to a sorted list
+ */
+const BITSET_LIMIT: u64 = 1 << 12;
+
+/**
+ * The winning numbers of a card, built once so looking up each of my numbers
+ * doesn't scan the whole list
+ */
+#[derive(Debug, Clone, PartialEq)]
+pub enum WinningSet {
+    /**
+     * Bit `n % 64` of word `n / 64` is set for every winning number `n`
+     */
+    Bits(Vec<u64>),
+    /**
+     * Sorted without duplicates
+     */
+    Sorted(Vec<u64>),
+}
+
+impl WinningSet {
+    pub fn new(nums: &[u64]) -> Self {
+        let max = nums.iter().copied().max().unwrap_or(0);
+
+        if max < BITSET_LIMIT {
+            let mut words = vec![0u64; max as usize / 64 + 1];
+
+            for num in nums {
+                words[*num as usize / 64] |= 1 << (num % 64);
+            }
+
+            return WinningSet::Bits(words);
+        }
+
+        let mut sorted = nums.to_vec();
+        sorted.sort_unstable();
+        sorted.dedup();
+
+        WinningSet::Sorted(sorted)
+    }
+
+    pub fn contains(&self, num: u64) -> bool {
+        match self {
+            WinningSet::Bits(words) => words
+                .get(num as usize / 64)
+                .is_some_and(|word| word & (1 << (num % 64)) != 0),
+            WinningSet::Sorted(sorted) => sorted.binary_search(&num).is_ok(),
+        }
+    }
+
+    /**
+     * How many of `nums` are winning numbers. A number I have twice counts
+     * twice, the same as checking them one by one.
+     */
+    pub fn count_matches(&self, nums: &[u64]) -> usize {
+        match self {
+            WinningSet::Bits(_) => nums.iter().filter(|num| self.contains(**num)).count(),
+            WinningSet::Sorted(sorted) => {
+                let mut nums = nums.to_vec();
+                nums.sort_unstable();
+
+                let (mut i, mut j, mut count) = (0, 0, 0);
+
+                while i < sorted.len() && j < nums.len() {
+                    match sorted[i].cmp(&nums[j]) {
+                        Ordering::Less => i += 1,
+                        Ordering::Greater => j += 1,
+                        Ordering::Equal => {
+                            count += 1;
+                            j += 1;
+                        },
+                    }
+                }
+
+                count
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashSet, time::Instant};
+
+    use aoc2023::rng::Rng;
+
+    use crate::matching::WinningSet;
+
+    fn naive(winning: &[u64], nums: &[u64]) -> usize {
+        let set: HashSet<&u64> = winning.iter().collect();
+
+        nums.iter().filter(|num| set.contains(num)).count()
+    }
+
+    fn random_nums(rng: &mut Rng, len: u64, max: u64) -> Vec<u64> {
+        (0..len).map(|_| rng.below(max)).collect()
+    }
+
+    #[test]
+    fn picks_a_representation() {
+        assert!(matches!(WinningSet::new(&[41, 48, 83]), WinningSet::Bits(words) if words.len() == 2));
+        assert!(matches!(WinningSet::new(&[]), WinningSet::Bits(_)));
+        assert_eq!(WinningSet::new(&[5000, 3, 5000]), WinningSet::Sorted(vec![3, 5000]));
+    }
+
+    #[test]
+    fn matches_naive_count() {
+        let mut rng = Rng::new(4);
+
+        for _ in 0..500 {
+            let max = *rng.pick(&[10, 100, 5000, u64::MAX]);
+            let (winning_len, nums_len) = (rng.below(12), rng.below(30));
+            let winning = random_nums(&mut rng, winning_len, max);
+            let mut nums = random_nums(&mut rng, nums_len, max);
+            // Make sure big sets share some numbers too
+            nums.extend(winning.iter().filter(|_| rng.chance(1, 2)));
+
+            assert_eq!(WinningSet::new(&winning).count_matches(&nums), naive(&winning, &nums));
+        }
+    }
+
+    /**
+     * `cargo test --release --bin day04 -- --ignored --nocapture`
+     */
+    #[test]
+    #[ignore]
+    fn benchmark_million_cards() {
+        let mut rng = Rng::new(1);
+        let cards: Vec<(Vec<u64>, Vec<u64>)> = (0..1_000_000)
+            .map(|_| (random_nums(&mut rng, 10, 100), random_nums(&mut rng, 25, 100)))
+            .collect();
+
+        let start = Instant::now();
+        let expected: usize = cards.iter().map(|(winning, nums)| naive(winning, nums)).sum();
+        println!("HashSet:      {:?}", start.elapsed());
+
+        let start = Instant::now();
+        let contains: usize = cards
+            .iter()
+            .map(|(winning, nums)| nums.iter().filter(|num| winning.contains(num)).count())
+            .sum();
+        println!("Vec contains: {:?}", start.elapsed());
+
+        let start = Instant::now();
+        let actual: usize = cards
+            .iter()
+            .map(|(winning, nums)| WinningSet::new(winning).count_matches(nums))
+            .sum();
+        println!("WinningSet:   {:?}", start.elapsed());
+
+        assert_eq!(actual, expected);
+        assert_eq!(contains, expected);
+    }
+}
@@ -0,0 +1,113 @@
+use aoc2023::bigint::BigInt;
+
+/**
+ * How many points a card with a number of matches is worth in part 1
+ */
+pub trait Scoring {
+    fn points(&self, matches: usize) -> BigInt;
+}
+
+/**
+ * One point for the first match, doubled for every match after it
+ */
+pub struct Doubling;
+
+impl Scoring for Doubling {
+    fn points(&self, matches: usize) -> BigInt {
+        match matches {
+            0 => BigInt::zero(),
+            _ => BigInt::from(2u64).pow(matches as u32 - 1),
+        }
+    }
+}
+
+/**
+ * One point per match
+ */
+pub struct Linear;
+
+impl Scoring for Linear {
+    fn points(&self, matches: usize) -> BigInt {
+        BigInt::from(matches as u64)
+    }
+}
+
+/**
+ * The nth Fibonacci number for n matches: 1, 1, 2, 3, 5, ...
+ */
+pub struct Fibonacci;
+
+impl Scoring for Fibonacci {
+    fn points(&self, matches: usize) -> BigInt {
+        let (mut a, mut b) = (BigInt::zero(), BigInt::from(1u64));
+
+        for _ in 0..matches {
+            let next = &a + &b;
+            a = b;
+            b = next;
+        }
+
+        a
+    }
+}
+
+/**
+ * Points looked up by number of matches, starting at 0 matches. Anything past
+ * the end of the table scores its last entry.
+ */
+pub struct Table(pub Vec<BigInt>);
+
+impl Scoring for Table {
+    fn points(&self, matches: usize) -> BigInt {
+        self.0
+            .get(matches)
+            .or(self.0.last())
+            .cloned()
+            .unwrap_or_default()
+    }
+}
+
+/**
+ * `doubling`, `linear`, `fibonacci` or `table:p0,p1,p2,...`
+ */
+pub fn parse_scoring(s: &str) -> Result<Box<dyn Scoring>, String> {
+    match s.trim().split_once(':') {
+        None if s.trim() == "doubling" => Ok(Box::new(Doubling)),
+        None if s.trim() == "linear" => Ok(Box::new(Linear)),
+        None if s.trim() == "fibonacci" => Ok(Box::new(Fibonacci)),
+        Some(("table", points)) => {
+            let points = points
+                .split(',')
+                .map(|p| p.trim().parse())
+                .collect::<Result<Vec<BigInt>, String>>()?;
+
+            Ok(Box::new(Table(points)))
+        },
+        _ => Err(format!("Unknown scoring rule: {}", s)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::scoring::{parse_scoring, Doubling, Fibonacci, Linear, Scoring};
+
+    fn points(scoring: &dyn Scoring) -> Vec<String> {
+        (0..7).map(|matches| scoring.points(matches).to_string()).collect()
+    }
+
+    #[test]
+    fn rules() {
+        assert_eq!(points(&Doubling), ["0", "1", "2", "4", "8", "16", "32"]);
+        assert_eq!(points(&Linear), ["0", "1", "2", "3", "4", "5", "6"]);
+        assert_eq!(points(&Fibonacci), ["0", "1", "1", "2", "3", "5", "8"]);
+        assert_eq!(points(parse_scoring("table:0,10,15").unwrap().as_ref()), ["0", "10", "15", "15", "15", "15", "15"]);
+        assert_eq!(Doubling.points(70).to_string(), "590295810358705651712");
+    }
+
+    #[test]
+    fn parse() {
+        assert!(parse_scoring("doubling").is_ok());
+        assert!(parse_scoring("table:1,x").is_err());
+        assert!(parse_scoring("squares").is_err());
+    }
+}
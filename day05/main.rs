@@ -1,16 +1,100 @@
-use std::{collections::VecDeque, fs, str::FromStr, thread::sleep, time::Duration};
+use std::{collections::{HashMap, HashSet}, env, str::FromStr};
+
+use aoc2023::{debug, input::{arg_value, read_input}, interval::{IntervalSet, RangeMap}, trace};
+use validate::validate;
+
+mod validate;
 
 fn main() {
-    let contents = fs::read_to_string("./exampleinput")
-        .expect("File not found");
+    let contents = read_input();
+
+    if env::args().any(|arg| arg == "--validate") {
+        let diagnostics = validate(&contents);
+
+        for diagnostic in diagnostics.iter() {
+            println!("{}", diagnostic);
+        }
+
+        println!("{} problem(s) found", diagnostics.len());
+        return;
+    }
 
     let almanac = match Almanac::from_str(&contents) {
         Err(err) => panic!("{}", err),
         Ok(v) => v,
     };
 
+    if let Some(query) = arg_value("--convert") {
+        let parsed = query
+            .split(':')
+            .collect::<Vec<_>>();
+
+        let (from, to, value) = match parsed[..] {
+            [from, to, value] => (from, to, value.parse().expect("Failed to parse --convert value")),
+            _ => panic!("Expected --convert from:to:value, got {}", query),
+        };
+
+        match almanac.convert(from, to, value) {
+            Err(err) => panic!("{}", err),
+            Ok(v) => println!("{} {} is {} {}", from, value, to, v),
+        }
+    }
+
+    if let Some(location) = arg_value("--seeds-for") {
+        let location = location.parse().expect("Failed to parse --seeds-for location");
+        let seeds = almanac
+            .find_seeds(location)
+            .iter()
+            .map(|seed| seed.to_string())
+            .collect::<Vec<_>>();
+
+        println!("Seeds for location {}: {}", location, seeds.join(", "));
+    }
+
+    let strategy = arg_value("--part2")
+        .map(|s| s.parse().unwrap_or_else(|err| panic!("{}", err)))
+        .unwrap_or_default();
+
     println!("Part 1: {}", part1(&almanac));
-    println!("Part 2: {}", part2(&almanac));
+    let part2 = match part2(&almanac, strategy) {
+        Err(err) => panic!("{}", err),
+        Ok(v) => v,
+    };
+
+    println!("Part 2: {}", part2);
+}
+
+/**
+ * How part 2 finds the lowest location of the seed ranges
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+enum Strategy {
+    /**
+     * Push the seed ranges through the categories one by one
+     */
+    #[default]
+    Ranges,
+    /**
+     * Collapse the almanac into a single category first
+     */
+    Collapse,
+    /**
+     * Try every location from 0 up until one of its seeds is in a seed range
+     */
+    Inverse,
+}
+
+impl FromStr for Strategy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "ranges" => Ok(Strategy::Ranges),
+            "collapse" => Ok(Strategy::Collapse),
+            "inverse" => Ok(Strategy::Inverse),
+            _ => Err(format!("Unknown part 2 strategy: {}", s)),
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -19,209 +103,200 @@ struct Almanac {
     categories: Vec<Category>,
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Default)]
 struct Category {
-    mappings: Vec<Mapping>,
+    source: String,
+    destination: String,
+    map: RangeMap,
 }
 
-#[derive(Debug, Clone, PartialEq)]
-struct Mapping {
-    source_start: u64,
-    source_end: u64,
-    destination_start: u64,
-    destination_end: u64,
-}
+impl Almanac {
+    /**
+     * The categories that lead from seed to location, they come first after
+     * parsing
+     */
+    fn seed_to_location(&self) -> &[Category] {
+        let mut current = "seed";
 
-impl Mapping {
-    fn new(source_start: u64, destination_start: u64, len: u64) -> Self {
-        Mapping {
-            source_start,
-            source_end: source_start + len,
-            destination_start,
-            destination_end: destination_start + len
-        }
-    }
+        let len = self.categories
+            .iter()
+            .take_while(|cat| {
+                let next = current != "location" && cat.source == current;
+                current = &cat.destination;
 
-    fn len(&self) -> u64 {
-        assert!(self.source_end - self.source_start == self.destination_end - self.destination_start);
+                next
+            })
+            .count();
 
-        self.source_end - self.source_start
+        &self.categories[..len]
     }
 
-    fn is_empty(&self) -> bool {
-        self.len() == 0
-    }
+    /**
+     * The seeds line read as pairs of a start and a length, the way part 2
+     * sees it
+     */
+    fn seed_ranges(&self) -> Result<IntervalSet, String> {
+        let line = || {
+            let seeds = self.seeds.iter().map(|seed| seed.to_string()).collect::<Vec<_>>();
 
-    fn shrink_start_by(&mut self, len: u64) {
-        self.source_start += len;
-        self.destination_start += len;
-    }
+            format!("seeds: {}", seeds.join(" "))
+        };
 
-    fn shrink_end_by(&mut self, len: u64) {
-        assert!(self.source_end >= len && self.destination_end >= len);
+        if ! self.seeds.len().is_multiple_of(2) {
+            return Err(format!("Seeds should come in pairs of a start and a length: {}", line()));
+        }
 
-        self.source_end -= len;
-        self.destination_end -= len;
+        self.seeds
+            .chunks(2)
+            .map(|chunk| chunk[0]
+                .checked_add(chunk[1])
+                .map(|end| chunk[0]..end)
+                .ok_or_else(|| format!(
+                    "Seed range starting at {} with a length of {} overflows u64: {}", chunk[0], chunk[1], line(),
+                ))
+            )
+            .collect()
     }
-}
 
-impl Almanac {
     fn find_seed_location(&self, seed: u64) -> u64 {
-        self.categories
+        self.seed_to_location()
             .iter()
             .fold(seed, |r, cat| cat.find_destination(r))
     }
-}
-
-impl Category {
-    fn merge(&self, source: &Self) -> Self {
-        let mut sources = source.mappings.clone();
-        let mut destinations = self.mappings.clone();
-        let mut merged: Vec<Mapping> = Vec::new();
-        sources.sort_by_key(|x| x.source_start);
-        destinations.sort_by_key(|x| x.source_start);
-        println!("Merging: \n\t{:?}\n\t{:?}", sources, destinations);
-
-        // Map sources
-        while ! sources.is_empty() {
-            let a = &sources[0];
-
-            if a.is_empty() {
-                sources.remove(0);
-                continue;
-            }
 
-            println!("Wot:\n\t{:?}", a);
-            let b = match destinations
-                .iter()
-                .find(|b| b.source_end > a.destination_start && b.source_start < a.destination_end) {
-                Some(b) => b,
-                None => {
-                    merged.push(sources.remove(0));
-                    continue;
-                }
-            };
-            println!("\t{:?}", b);
-
-         
-            if a.destination_start < b.source_start {
-                let len = b.source_start - a.destination_start;
-                println!("if {:?}", Mapping::new(a.source_start, a.destination_start, len));
-                merged.push(Mapping::new(a.source_start, a.destination_start, len));
-                (&mut sources[0]).shrink_start_by(len);
-
-                continue;
-            }
-
-            if a.destination_start >= b.source_start {
-                let len = (a.destination_end - a.destination_start).min(b.source_end - a.destination_start);
-                let shift = a.destination_start - b.source_start;
-                println!("else {:?}", Mapping::new(a.source_start, b.destination_start + shift, len));
-                merged.push(Mapping::new(a.source_start, b.destination_start + shift, len));
-                (&mut sources[0]).shrink_start_by(len);
-
-                continue;
-            }
-        }
-
-        merged.sort_by_key(|x| x.source_start);
-
-        println!("\nOut mappings:");
-        for mapping in merged.iter() {
-            println!("{:?}", mapping)
-        }
-        println!("-------------------------------");
-
-        // Fill gaps with destinations
-        let mut i = 0;
-        
-        let mut fallthrough_gaps: Vec<Mapping> = Vec::new();
+    /**
+     * Every seed that ends up at `location`, sorted
+     */
+    fn find_seeds(&self, location: u64) -> Vec<u64> {
+        self.seed_to_location()
+            .iter()
+            .rev()
+            .fold(vec![location], |destinations, cat| {
+                let mut sources: Vec<u64> = destinations
+                    .into_iter()
+                    .flat_map(|d| cat.find_sources(d))
+                    .collect();
+
+                sources.sort_unstable();
+                sources.dedup();
+
+                sources
+            })
+    }
 
-        while ! destinations.is_empty() && i < merged.len() {
-            let x = &destinations[0];
-            let y = &merged[i];
+    /**
+     * A single category mapping seeds straight to locations
+     */
+    fn collapse(&self) -> Category {
+        let identity = Category {
+            source: "seed".to_owned(),
+            destination: "seed".to_owned(),
+            map: RangeMap::new(),
+        };
 
-            if x.is_empty() {
-                println!("Found empty {:?}, skipping...", x);
-                destinations.remove(0);
-                continue;
-            }
+        self.seed_to_location()
+            .iter()
+            .fold(identity, |merged, cat| cat.merge(&merged))
+    }
 
-            println!("Comparing\n\t{:?}\n\t{:?}", x, y);
-            
-            if x.source_start < y.source_start {
-                let len = x.len().min(y.source_start - x.source_start);
-                fallthrough_gaps.push(Mapping::new(x.source_start, x.destination_start, len));
-                (&mut destinations[0]).shrink_start_by(len);
-                println!("If");
+    /**
+     * Indices of the categories that convert `from` into `to`, in the order
+     * they have to be applied
+     */
+    fn chain_indices(&self, from: &str, to: &str) -> Result<Vec<usize>, String> {
+        let mut by_source: HashMap<&str, usize> = HashMap::new();
 
-                continue;
+        for (i, cat) in self.categories.iter().enumerate() {
+            if by_source.insert(&cat.source, i).is_some() {
+                return Err(format!("More than one category converts from {}", cat.source));
             }
+        }
 
-            if x.source_start < y.source_end && x.source_end > y.source_start {
-                let len = x.len().min(y.source_end - x.source_start);
-                (&mut destinations[0]).shrink_start_by(len);
-                println!("Else");
+        let mut chain: Vec<usize> = Vec::new();
+        let mut seen: HashSet<&str> = HashSet::new();
+        let mut current = from;
 
-                continue;
+        while current != to {
+            if ! seen.insert(current) {
+                return Err(format!("Categories form a cycle at {} before reaching {}", current, to));
             }
 
-            i += 1;
-        }
-
-        fallthrough_gaps.extend(destinations);
+            let i = *by_source
+                .get(current)
+                .ok_or_else(|| format!("No category converts from {} on the way to {}", current, to))?;
 
-        println!("Fallthrough:");
-        for mapping in fallthrough_gaps.iter() {
-            println!("{:?}", mapping)
+            chain.push(i);
+            current = &self.categories[i].destination;
         }
 
-        merged.extend(fallthrough_gaps);
-        merged.sort_by_key(|x| x.source_start);
-
-        Category { mappings: merged }
+        Ok(chain)
     }
 
     /**
-     * Find the destination of a source by using binary search
+     * The categories that convert `from` into `to`, in the order they have to
+     * be applied
      */
-    fn find_destination(&self, source: u64) -> u64 {
-        match self.find_matching_mapping(source) {
-            Some(category) => {
-                let shift = source - category.source_start;
-
-                return category.destination_start + shift;
-            },
-            None => source,
-        }
+    fn chain(&self, from: &str, to: &str) -> Result<Vec<&Category>, String> {
+        Ok(
+            self.chain_indices(from, to)?
+                .into_iter()
+                .map(|i| &self.categories[i])
+                .collect()
+        )
     }
 
-    fn find_matching_mapping(&self, source: u64) -> Option<&Mapping> {
-        let (mut s, mut e) = (0, self.mappings.len());
+    /**
+     * Converts a value between any two categories by name, eg. soil to humidity
+     */
+    fn convert(&self, from: &str, to: &str, value: u64) -> Result<u64, String> {
+        Ok(
+            self.chain(from, to)?
+                .into_iter()
+                .fold(value, |r, cat| cat.find_destination(r))
+        )
+    }
+}
 
-        while s < e {
-            let mid = (e - s) / 2 + s;
-            let mid_item = &self.mappings[mid];
-            
-            if mid_item.source_start <= source
-               && source < mid_item.source_end {
+impl Category {
+    /**
+     * Composes two categories into one that maps straight from the sources of
+     * `source` to the destinations of `self`, the same as looking a value up
+     * in `source` and then in `self`
+     */
+    fn merge(&self, source: &Self) -> Self {
+        let map = self.map.compose(&source.map);
 
-                return Some(mid_item);
-            }
+        debug!(
+            "Merged {}-to-{} into {}-to-{}: {} piece(s)",
+            self.source, self.destination, source.source, source.destination, map.pieces().len(),
+        );
 
-            if source < mid_item.source_start {
-                e = mid;
-                continue;
-            }
+        Category {
+            source: source.source.clone(),
+            destination: self.destination.clone(),
+            map,
+        }
+    }
 
-            if mid_item.source_end <= source {
-                s = mid + 1;
-                continue;
-            }
+    /**
+     * Where every value of `sources` ends up, sources that no mapping covers
+     * map to themselves
+     */
+    fn map_ranges(&self, sources: &IntervalSet) -> IntervalSet {
+        self.map.apply(sources)
+    }
 
-        }
+    /**
+     * Every source that maps to `destination`. Mappings can send different
+     * sources to the same destination, and a source no mapping covers maps to
+     * itself, so there can be several of them or none at all.
+     */
+    fn find_sources(&self, destination: u64) -> Vec<u64> {
+        self.map.preimage(destination)
+    }
 
-        None
+    fn find_destination(&self, source: u64) -> u64 {
+        self.map.get(source)
     }
 }
 
@@ -241,50 +316,73 @@ impl FromStr for Almanac {
 
         let categories = rest_str
             .split("\n\n")
-            .map(|cs| cs
-                .split("\n")
-                .skip(1)
-                .map(Mapping::from_str)
-                .collect::<Result<Vec<Mapping>, String>>()
-                .map_err(|x| format!("{x} | Category: {cs}"))
-                .map(|mut mappings| {
-                    mappings.sort_by_key(|x| x.source_start);
-
-                    Category { mappings }
-                })
-            )
+            .map(Category::from_str)
             .collect::<Result<Vec<Category>, String>>()?;
 
-        Ok(
-            Self {
-                seeds,
-                categories,
-            }
-        )
+        let mut almanac = Self {
+            seeds,
+            categories,
+        };
+
+        // Blocks can come in any order, the ones from seed to location go
+        // first so they can be applied one after the other
+        let chain = almanac.chain_indices("seed", "location")?;
+        let position = |i: usize| chain.iter().position(|c| *c == i).unwrap_or(chain.len());
+        let mut indexed: Vec<(usize, Category)> = almanac.categories.drain(..).enumerate().collect();
+
+        indexed.sort_by_key(|(i, _)| position(*i));
+        almanac.categories = indexed.into_iter().map(|(_, cat)| cat).collect();
+
+        Ok(almanac)
     }
 }
 
-impl FromStr for Mapping {
+/**
+ * A `source-to-destination map:` header followed by one mapping per line
+ */
+impl FromStr for Category {
     type Err = String;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let nums = s
+        let (header, mappings_str) = s.split_once('\n').unwrap_or((s, ""));
+
+        let (source, destination) = header
             .trim()
-            .split_whitespace()
-            .map(|s| s.parse().ok())
-            .collect::<Option<Vec<u64>>>()
-            .ok_or(format!("Failed to parse mapping: {}", s))?;
-
-        if nums.len() != 3 {
-            return Err(
-                format!("Mapping has an incorect number of parameters {}", s)
-            );
-        }
+            .strip_suffix(" map:")
+            .and_then(|names| names.split_once("-to-"))
+            .ok_or(format!("Failed to parse category header: {}", header))?;
+
+        let mut map = RangeMap::new();
 
-        match nums[..] {
-            [a, b, c] => Ok(Self::new(a, b, c)),
-            _ => Err(format!("Mapping has an incorect number of parameters {}", s)),
+        for line in mappings_str.lines() {
+            let (destination_start, source_start, len) = parse_mapping(line)?;
+
+            map.insert(source_start, destination_start, len).map_err(|x| format!("{x} | Category: {s}"))?;
         }
+
+        Ok(
+            Self {
+                source: source.to_owned(),
+                destination: destination.to_owned(),
+                map,
+            }
+        )
+    }
+}
+
+/**
+ * A `destination source length` line, as those three numbers
+ */
+fn parse_mapping(s: &str) -> Result<(u64, u64, u64), String> {
+    let nums = s
+        .split_whitespace()
+        .map(|s| s.parse().ok())
+        .collect::<Option<Vec<u64>>>()
+        .ok_or(format!("Failed to parse mapping: {}", s))?;
+
+    match nums[..] {
+        [destination_start, source_start, len] => Ok((destination_start, source_start, len)),
+        _ => Err(format!("Mapping has an incorect number of parameters {}", s)),
     }
 }
 
@@ -299,94 +397,324 @@ fn part1(almanac: &Almanac) -> String {
     lowest_location.to_string()
 }
 
-fn part2(almanac: &Almanac) -> String {
-    let seed_ranges: Vec<_> = almanac.seeds
-        .chunks(2)
-        .map(|chunk| (chunk[0], (chunk[0] + chunk[1])))
-        .collect();
+fn part2(almanac: &Almanac, strategy: Strategy) -> Result<String, String> {
+    let seed_ranges = almanac.seed_ranges()?;
+
+    if seed_ranges.is_empty() {
+        return Err("Should have at least 1 seed range".to_owned());
+    }
+
+    let location_ranges = match strategy {
+        Strategy::Ranges => almanac.seed_to_location()
+            .iter()
+            .fold(seed_ranges, |ranges, cat| {
+                let mapped = cat.map_ranges(&ranges);
+
+                debug!("{}-to-{}: {} range(s) became {}", cat.source, cat.destination, ranges.ranges().len(), mapped.ranges().len());
+                trace!("{:?}", mapped.ranges());
 
-    let mut lowest_location = u64::MAX;
+                mapped
+            }),
+        Strategy::Collapse => almanac.collapse().map_ranges(&seed_ranges),
+        Strategy::Inverse => {
+            let lowest_location = (0..)
+                .find(|location| {
+                    let seeds = almanac.find_seeds(*location);
 
-    let merged = almanac.categories.clone().into_iter().rev().take(3).reduce(|a, b| a.merge(&b));
+                    trace!("Location {} comes from seeds {:?}", location, seeds);
 
-    println!("Final mapping: {:#?}", merged);
+                    seeds.into_iter().any(|seed| seed_ranges.contains(seed))
+                })
+                .expect("Some seed has to end up at a location");
+
+            debug!("Location {} is the first one with a seed in range", lowest_location);
+
+            return Ok(lowest_location.to_string());
+        },
+    };
+
+    let lowest_location = location_ranges
+        .min()
+        .expect("Should have at least 1 location");
 
-    "".to_owned()
+    Ok(lowest_location.to_string())
 }
 
 
 
 #[cfg(test)]
 mod tests {
-    use crate::{Category, Mapping};
+    use std::str::FromStr;
+
+    use aoc2023::{interval::{IntervalSet, RangeMap}, rng::Rng};
+
+    use crate::{part1, part2, Almanac, Category, Strategy};
+
+    /**
+     * Source start, destination start and length of each mapping
+     */
+    fn category(mappings: &[(u64, u64, u64)]) -> Category {
+        let mut map = RangeMap::new();
+
+        for (source, destination, len) in mappings {
+            map.insert(*source, *destination, *len).unwrap();
+        }
+
+        Category { map, ..Default::default() }
+    }
+
+    /**
+     * Mappings with sources that don't overlap, possibly with gaps between them
+     */
+    fn random_category(rng: &mut Rng) -> Category {
+        let mut mappings = vec![];
+        let mut start = rng.below(10);
+
+        for _ in 0..rng.below(5) {
+            let len = rng.range(1, 20);
+            mappings.push((start, rng.below(100), len));
+            start += len + rng.below(10);
+        }
+
+        category(&mappings)
+    }
+
+    /**
+     * Names the categories so they lead from seed to location in order
+     */
+    fn seed_to_location(mut categories: Vec<Category>) -> Vec<Category> {
+        let len = categories.len();
+
+        for (i, cat) in categories.iter_mut().enumerate() {
+            cat.source = if i == 0 { "seed".to_owned() } else { format!("step{}", i) };
+            cat.destination = if i + 1 == len { "location".to_owned() } else { format!("step{}", i + 1) };
+        }
+
+        categories
+    }
+
+    #[test]
+    fn example() {
+        let almanac = Almanac::from_str(include_str!("exampleinput")).unwrap();
+
+        assert_eq!(almanac.categories[0].map.pieces()[0], (50..98, 52));
+        assert_eq!(part1(&almanac), "35");
+
+        for strategy in [Strategy::Ranges, Strategy::Collapse, Strategy::Inverse] {
+            assert_eq!(part2(&almanac, strategy).unwrap(), "46");
+        }
+    }
+
+    #[test]
+    fn rejects_bad_seed_ranges() {
+        let almanac = |seeds: &str| Almanac::from_str(&format!("{}\n\nseed-to-location map:\n0 1 2", seeds)).unwrap();
+
+        assert_eq!(
+            part2(&almanac("seeds: 79 14 55"), Strategy::Ranges).unwrap_err(),
+            "Seeds should come in pairs of a start and a length: seeds: 79 14 55",
+        );
+        assert_eq!(
+            part2(&almanac("seeds: 18446744073709551615 2"), Strategy::Ranges).unwrap_err(),
+            "Seed range starting at 18446744073709551615 with a length of 2 overflows u64: seeds: 18446744073709551615 2",
+        );
+        assert_eq!(part1(&almanac("seeds: 79 14 55")), "14");
+    }
 
-    // #[test]
+    #[test]
     fn test1() {
-        let a = Category {
-            mappings: vec![
-                Mapping::new(0, 69, 1),
-                Mapping::new(1, 0, 69),
-            ],
-        };
+        let a = category(&[(0, 69, 1), (1, 0, 69)]);
 
-        let b = Category {
-            mappings: vec![
-                Mapping::new(60, 56, 37),
-                Mapping::new(56, 93, 4),
-            ],
-        };
+        let b = category(&[(60, 56, 37), (56, 93, 4)]);
 
 
         let actual = b.merge(&a);
-        let expected = Category {
-            mappings: vec![
-                Mapping::new(0, 65, 1),
-                Mapping::new(1, 0, 56),
-                Mapping::new(57, 93, 4),
-                Mapping::new(61, 56, 9),
-                Mapping::new(70, 66, 27),
-            ],
-        };
+        let expected = category(&[
+            (0, 65, 1),
+            (1, 0, 56),
+            (57, 93, 4),
+            (61, 56, 9),
+            (70, 66, 27),
+        ]);
 
         assert_eq!(expected, actual);
     }
 
     #[test]
     fn test2() {
-        let a = Category {
-            mappings: vec![
-                Mapping::new(0, 65, 1),
-                Mapping::new(1, 0, 56),
-                Mapping::new(57, 93, 4),
-                Mapping::new(61, 56, 9),
-                Mapping::new(70, 66, 27),
-            ],
-        };
+        let a = category(&[
+            (0, 65, 1),
+            (1, 0, 56),
+            (57, 93, 4),
+            (61, 56, 9),
+            (70, 66, 27),
+        ]);
 
-        let b = Category {
-            mappings: vec![
-                Mapping::new(45, 77, 23),
-                Mapping::new(68, 64, 13),
-                Mapping::new(81, 45, 19),
-            ],
-        };
+        let b = category(&[
+            (45, 77, 23),
+            (68, 64, 13),
+            (81, 45, 19),
+        ]);
 
 
         let actual = a.merge(&b);
-        let expected = Category {
-            mappings: vec![
-                Mapping::new(0, 65, 1),
-                Mapping::new(1, 0, 44),
-                Mapping::new(45, 73, 20),
-                Mapping::new(65, 97, 3),
-                Mapping::new(68, 59, 6),
-                Mapping::new(74, 66, 7),
-                Mapping::new(81, 44, 12),
-                Mapping::new(93, 93, 4),
-                Mapping::new(97, 56, 3),
-            ],
-        };
+        let expected = category(&[
+            (0, 65, 1),
+            (1, 0, 44),
+            (45, 73, 20),
+            (65, 97, 3),
+            (68, 59, 6),
+            (74, 66, 7),
+            (81, 44, 12),
+            (93, 93, 4),
+            (97, 56, 3),
+        ]);
 
         assert_eq!(expected, actual);
     }
+
+    #[test]
+    fn map_ranges_splits_on_mapping_edges() {
+        let category = category(&[
+            (10, 100, 5),
+            (15, 0, 5),
+            (30, 50, 10),
+        ]);
+
+        let ranges = |ranges: &[(u64, u64)]| ranges
+            .iter()
+            .map(|(start, end)| *start..*end)
+            .collect::<IntervalSet>();
+
+        assert_eq!(
+            category.map_ranges(&ranges(&[(5, 18), (25, 45)])),
+            ranges(&[(0, 3), (5, 10), (25, 30), (40, 45), (50, 60), (100, 105)]),
+        );
+        assert_eq!(category.map_ranges(&ranges(&[(11, 12)])), ranges(&[(101, 102)]));
+    }
+
+    #[test]
+    fn part2_matches_brute_force() {
+        let mut rng = Rng::new(5);
+
+        for _ in 0..100 {
+            let categories = (0..rng.range(1, 5)).map(|_| random_category(&mut rng)).collect();
+
+            let seeds = (0..2 * rng.range(1, 4)).map(|_| rng.below(60)).collect();
+            let almanac = Almanac { seeds, categories: seed_to_location(categories) };
+
+            let expected = almanac.seeds
+                .chunks(2)
+                .flat_map(|chunk| chunk[0]..(chunk[0] + chunk[1]))
+                .map(|seed| almanac.find_seed_location(seed))
+                .min();
+
+            if let Some(expected) = expected {
+                assert_eq!(part2(&almanac, Strategy::Ranges).unwrap(), expected.to_string());
+                assert_eq!(part2(&almanac, Strategy::Collapse).unwrap(), expected.to_string());
+                assert_eq!(part2(&almanac, Strategy::Inverse).unwrap(), expected.to_string());
+            }
+        }
+    }
+
+    #[test]
+    fn merge_matches_lookups() {
+        let mut rng = Rng::new(6);
+
+        for _ in 0..300 {
+            let (a, b) = (random_category(&mut rng), random_category(&mut rng));
+            let merged = b.merge(&a);
+
+            for x in 0..250 {
+                assert_eq!(merged.find_destination(x), b.find_destination(a.find_destination(x)), "{:?} {:?}", a, b);
+            }
+        }
+    }
+
+    #[test]
+    fn almanac_collapses_into_one_category() {
+        let mut rng = Rng::new(7);
+
+        for _ in 0..100 {
+            let categories = (0..rng.range(1, 8)).map(|_| random_category(&mut rng)).collect();
+            let almanac = Almanac { seeds: vec![], categories: seed_to_location(categories) };
+            let collapsed = almanac.collapse();
+
+            assert!(collapsed.map.pieces().windows(2).all(|w| w[0].0.end <= w[1].0.start));
+
+            for _ in 0..100 {
+                let seed = rng.below(300);
+
+                assert_eq!(collapsed.find_destination(seed), almanac.find_seed_location(seed));
+            }
+        }
+    }
+
+    #[test]
+    fn categories_by_name() {
+        let example = include_str!("exampleinput");
+        let (seeds, blocks) = example.trim().split_once("\n\n").unwrap();
+        let mut blocks = blocks.split("\n\n").collect::<Vec<_>>();
+        blocks.reverse();
+
+        let shuffled = Almanac::from_str(&format!("{}\n\n{}", seeds, blocks.join("\n\n"))).unwrap();
+        let almanac = Almanac::from_str(example).unwrap();
+
+        assert_eq!(shuffled.categories, almanac.categories);
+        assert_eq!(almanac.categories[0].source, "seed");
+        assert_eq!(almanac.categories.last().unwrap().destination, "location");
+
+        let soil = almanac.convert("seed", "soil", 79).unwrap();
+        let humidity = almanac.convert("soil", "humidity", soil).unwrap();
+
+        assert_eq!(almanac.convert("humidity", "location", humidity), Ok(almanac.find_seed_location(79)));
+        assert_eq!(almanac.convert("soil", "soil", 5), Ok(5));
+        assert!(almanac.convert("location", "seed", 5).is_err());
+    }
+
+    #[test]
+    fn rejects_broken_graphs() {
+        let parse = |blocks: &str| Almanac::from_str(&format!("seeds: 1\n\n{}", blocks));
+
+        assert_eq!(
+            parse("seed-to-soil map:\n1 2 3\n\nsoil-to-seed map:\n1 2 3").unwrap_err(),
+            "Categories form a cycle at seed before reaching location",
+        );
+        assert_eq!(
+            parse("seed-to-soil map:\n1 2 3\n\nwater-to-location map:\n1 2 3").unwrap_err(),
+            "No category converts from soil on the way to location",
+        );
+        assert_eq!(
+            parse("seed-to-location map:\n\nseed-to-soil map:").unwrap_err(),
+            "More than one category converts from seed",
+        );
+        assert!(parse("seed to location:\n1 2 3").is_err());
+        assert!(parse("seed-to-location map:\n0 18446744073709551615 1").is_err());
+    }
+
+    #[test]
+    fn find_sources_inverts_find_destination() {
+        let category = category(&[(0, 10, 5), (20, 0, 5)]);
+
+        // 12 comes from 2 and is not mapped itself, 3 comes from 23 but 3 is mapped away
+        assert_eq!(category.find_sources(12), vec![2, 12]);
+        assert_eq!(category.find_sources(3), vec![23]);
+        assert_eq!(category.find_sources(22), vec![]);
+        assert_eq!(category.find_sources(30), vec![30]);
+
+        let mut rng = Rng::new(8);
+
+        for _ in 0..100 {
+            let categories = (0..rng.range(1, 5)).map(|_| random_category(&mut rng)).collect();
+            let almanac = Almanac { seeds: vec![], categories: seed_to_location(categories) };
+
+            for location in 0..150 {
+                let expected = (0..300)
+                    .filter(|seed| almanac.find_seed_location(*seed) == location)
+                    .collect::<Vec<_>>();
+                let seeds = almanac.find_seeds(location);
+
+                assert_eq!(seeds.into_iter().filter(|seed| *seed < 300).collect::<Vec<_>>(), expected);
+            }
+        }
+    }
 }
@@ -0,0 +1,170 @@
+use std::fmt;
+
+use aoc2023::interval::checked_range;
+
+use crate::parse_mapping;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    /**
+     * 1 based
+     */
+    pub line: usize,
+    /**
+     * Header of the block the line is in, without ` map:`
+     */
+    pub category: String,
+    pub text: String,
+    pub message: String,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {} ({}): {}\n    {}", self.line, self.category, self.message, self.text)
+    }
+}
+
+/**
+ * Problems with the mappings of an almanac that parsing doesn't catch:
+ * mappings in the same category with overlapping sources (the lookup would
+ * pick one of them arbitrarily), mappings of length zero, and ranges that
+ * run past `u64::MAX`.
+ */
+pub fn validate(input: &str) -> Vec<Diagnostic> {
+    let mut diagnostics = vec![];
+    let mut category = String::new();
+    // Source ranges of the current category with the line they are on
+    let mut sources: Vec<(u64, u64, usize, &str)> = vec![];
+
+    let lines = input.lines().enumerate().map(|(i, text)| (i + 1, text.trim()));
+
+    for (line, text) in lines.chain(std::iter::once((0, ""))) {
+        if text.is_empty() || text.ends_with("map:") {
+            sources.sort();
+
+            // Furthest reaching source range so far and its line, a range can
+            // overlap one that started long before it
+            let mut reach: Option<(u64, usize)> = None;
+
+            for (start, end, line, text) in sources.iter().copied() {
+                match reach {
+                    Some((reach_end, reach_line)) if start < reach_end => diagnostics.push(Diagnostic {
+                        line,
+                        category: category.clone(),
+                        text: text.to_owned(),
+                        message: format!("Source range overlaps the one on line {}", reach_line),
+                    }),
+                    _ => (),
+                }
+
+                if reach.is_none_or(|(reach_end, _)| end > reach_end) {
+                    reach = Some((end, line));
+                }
+            }
+
+            sources.clear();
+
+            if let Some(header) = text.strip_suffix(" map:") {
+                category = header.to_owned();
+            }
+
+            continue;
+        }
+
+        if text.starts_with("seeds:") {
+            continue;
+        }
+
+        let mut report = |message: String| diagnostics.push(
+            Diagnostic { line, category: category.clone(), text: text.to_owned(), message }
+        );
+
+        let (destination, source, len) = match parse_mapping(text) {
+            Err(err) => {
+                report(err);
+                continue;
+            },
+            Ok(v) => v,
+        };
+
+        if len == 0 {
+            report("Mapping has a length of zero".to_owned());
+            continue;
+        }
+
+        let source_range = checked_range("Source", source, len);
+
+        for result in [&source_range, &checked_range("Destination", destination, len)] {
+            if let Err(err) = result {
+                report(err.clone());
+            }
+        }
+
+        if let Ok(range) = source_range {
+            sources.push((range.start, range.end, line, text));
+        }
+    }
+
+    diagnostics.sort_by_key(|d| d.line);
+
+    diagnostics
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::validate::validate;
+
+    #[test]
+    fn clean_example() {
+        assert!(validate(include_str!("exampleinput")).is_empty());
+    }
+
+    #[test]
+    fn finds_problems() {
+        let input = "seeds: 1 2\n\n\
+            seed-to-soil map:\n\
+            50 98 2\n\
+            52 50 49\n\
+            0 10 0\n\n\
+            soil-to-location map:\n\
+            0 18446744073709551615 1\n\
+            1 2\n";
+
+        let messages = validate(input)
+            .into_iter()
+            .map(|d| (d.line, d.category, d.message))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            messages,
+            vec![
+                (4, "seed-to-soil".to_owned(), "Source range overlaps the one on line 5".to_owned()),
+                (6, "seed-to-soil".to_owned(), "Mapping has a length of zero".to_owned()),
+                (
+                    9,
+                    "soil-to-location".to_owned(),
+                    "Source range starting at 18446744073709551615 with a length of 1 overflows u64".to_owned(),
+                ),
+                (10, "soil-to-location".to_owned(), "Mapping has an incorect number of parameters 1 2".to_owned()),
+            ],
+        );
+    }
+
+    #[test]
+    fn nested_overlaps() {
+        let input = "seeds: 1 2\n\nseed-to-soil map:\n0 0 100\n200 10 5\n300 30 5\n400 100 5\n";
+
+        let messages = validate(input)
+            .into_iter()
+            .map(|d| (d.line, d.message))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            messages,
+            vec![
+                (5, "Source range overlaps the one on line 4".to_owned()),
+                (6, "Source range overlaps the one on line 4".to_owned()),
+            ],
+        );
+    }
+}
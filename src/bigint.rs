@@ -0,0 +1,399 @@
+use std::{
+    cmp::Ordering,
+    fmt,
+    iter::{Product, Sum},
+    ops::{Add, AddAssign, Mul, Neg, Sub},
+    str::FromStr,
+};
+
+const BASE: u64 = 1_000_000_000;
+const BASE_DIGITS: usize = 9;
+
+/**
+ * Arbitrary precision signed integer, for answers that don't fit in a u64.
+ * Only has the handful of operations the solutions need.
+ */
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct BigInt {
+    negative: bool,
+    /**
+     * Base 10^9 digits, least significant first, without trailing zeros.
+     * Zero has no limbs and is never negative.
+     */
+    limbs: Vec<u32>,
+}
+
+impl BigInt {
+    pub fn zero() -> Self {
+        Self::default()
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.limbs.is_empty()
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.negative
+    }
+
+    pub fn to_u64(&self) -> Option<u64> {
+        if self.negative || self.limbs.len() > 3 {
+            return None;
+        }
+
+        self.limbs
+            .iter()
+            .rev()
+            .try_fold(0u64, |acc, limb| acc.checked_mul(BASE)?.checked_add(*limb as u64))
+    }
+
+    pub fn pow(&self, mut exp: u32) -> Self {
+        let mut base = self.clone();
+        let mut out = BigInt::from(1u64);
+
+        while exp > 0 {
+            if exp & 1 == 1 {
+                out = &out * &base;
+            }
+
+            base = &base * &base;
+            exp >>= 1;
+        }
+
+        out
+    }
+
+    fn from_parts(negative: bool, mut limbs: Vec<u32>) -> Self {
+        while limbs.last() == Some(&0) {
+            limbs.pop();
+        }
+
+        Self {
+            negative: negative && ! limbs.is_empty(),
+            limbs,
+        }
+    }
+}
+
+fn cmp_magnitude(a: &[u32], b: &[u32]) -> Ordering {
+    a.len()
+        .cmp(&b.len())
+        .then_with(|| a.iter().rev().cmp(b.iter().rev()))
+}
+
+fn add_magnitude(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let mut out = Vec::with_capacity(a.len().max(b.len()) + 1);
+    let mut carry = 0;
+
+    for i in 0..a.len().max(b.len()) {
+        let sum = *a.get(i).unwrap_or(&0) as u64 + *b.get(i).unwrap_or(&0) as u64 + carry;
+
+        out.push((sum % BASE) as u32);
+        carry = sum / BASE;
+    }
+
+    if carry > 0 {
+        out.push(carry as u32);
+    }
+
+    out
+}
+
+/**
+ * `a - b`, where `a` has to be at least as large as `b`
+ */
+fn sub_magnitude(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let mut out = Vec::with_capacity(a.len());
+    let mut borrow = 0;
+
+    for (i, limb) in a.iter().enumerate() {
+        let mut diff = *limb as i64 - *b.get(i).unwrap_or(&0) as i64 - borrow;
+
+        borrow = 0;
+
+        if diff < 0 {
+            diff += BASE as i64;
+            borrow = 1;
+        }
+
+        out.push(diff as u32);
+    }
+
+    out
+}
+
+fn mul_magnitude(a: &[u32], b: &[u32]) -> Vec<u32> {
+    if a.is_empty() || b.is_empty() {
+        return vec![];
+    }
+
+    let mut out = vec![0u64; a.len() + b.len()];
+
+    for (i, x) in a.iter().enumerate() {
+        let mut carry = 0;
+
+        for (j, y) in b.iter().enumerate() {
+            let cur = out[i + j] + *x as u64 * *y as u64 + carry;
+
+            out[i + j] = cur % BASE;
+            carry = cur / BASE;
+        }
+
+        out[i + b.len()] += carry;
+    }
+
+    out.into_iter().map(|limb| limb as u32).collect()
+}
+
+impl From<u64> for BigInt {
+    fn from(n: u64) -> Self {
+        let mut limbs = vec![];
+        let mut n = n;
+
+        while n > 0 {
+            limbs.push((n % BASE) as u32);
+            n /= BASE;
+        }
+
+        Self::from_parts(false, limbs)
+    }
+}
+
+impl From<i64> for BigInt {
+    fn from(n: i64) -> Self {
+        let magnitude = Self::from(n.unsigned_abs());
+
+        Self::from_parts(n < 0, magnitude.limbs)
+    }
+}
+
+impl From<u32> for BigInt {
+    fn from(n: u32) -> Self {
+        Self::from(n as u64)
+    }
+}
+
+impl FromStr for BigInt {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (negative, digits) = match s.strip_prefix('-') {
+            Some(digits) => (true, digits),
+            None => (false, s.strip_prefix('+').unwrap_or(s)),
+        };
+
+        if digits.is_empty() || ! digits.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(format!("Failed to parse number: {}", s));
+        }
+
+        let limbs = digits
+            .as_bytes()
+            .rchunks(BASE_DIGITS)
+            .map(|chunk| chunk.iter().fold(0u32, |acc, b| acc * 10 + (b - b'0') as u32))
+            .collect();
+
+        Ok(Self::from_parts(negative, limbs))
+    }
+}
+
+impl fmt::Display for BigInt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut digits = self.limbs
+            .last()
+            .map(|limb| limb.to_string())
+            .unwrap_or_else(|| "0".to_owned());
+
+        for limb in self.limbs.iter().rev().skip(1) {
+            digits.push_str(&format!("{:09}", limb));
+        }
+
+        f.pad_integral(! self.negative, "", &digits)
+    }
+}
+
+impl Ord for BigInt {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self.negative, other.negative) {
+            (false, true) => Ordering::Greater,
+            (true, false) => Ordering::Less,
+            (false, false) => cmp_magnitude(&self.limbs, &other.limbs),
+            (true, true) => cmp_magnitude(&other.limbs, &self.limbs),
+        }
+    }
+}
+
+impl PartialOrd for BigInt {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Neg for BigInt {
+    type Output = BigInt;
+
+    fn neg(self) -> Self::Output {
+        Self::from_parts(! self.negative, self.limbs)
+    }
+}
+
+impl Add<&BigInt> for &BigInt {
+    type Output = BigInt;
+
+    fn add(self, other: &BigInt) -> Self::Output {
+        if self.negative == other.negative {
+            return BigInt::from_parts(self.negative, add_magnitude(&self.limbs, &other.limbs));
+        }
+
+        match cmp_magnitude(&self.limbs, &other.limbs) {
+            Ordering::Less => BigInt::from_parts(other.negative, sub_magnitude(&other.limbs, &self.limbs)),
+            _ => BigInt::from_parts(self.negative, sub_magnitude(&self.limbs, &other.limbs)),
+        }
+    }
+}
+
+impl Add for BigInt {
+    type Output = BigInt;
+
+    fn add(self, other: BigInt) -> Self::Output {
+        &self + &other
+    }
+}
+
+impl AddAssign<&BigInt> for BigInt {
+    fn add_assign(&mut self, other: &BigInt) {
+        *self = &*self + other;
+    }
+}
+
+impl AddAssign for BigInt {
+    fn add_assign(&mut self, other: BigInt) {
+        *self += &other;
+    }
+}
+
+impl Sub<&BigInt> for &BigInt {
+    type Output = BigInt;
+
+    fn sub(self, other: &BigInt) -> Self::Output {
+        self + &(-other.clone())
+    }
+}
+
+impl Sub for BigInt {
+    type Output = BigInt;
+
+    fn sub(self, other: BigInt) -> Self::Output {
+        &self - &other
+    }
+}
+
+impl Mul<&BigInt> for &BigInt {
+    type Output = BigInt;
+
+    fn mul(self, other: &BigInt) -> Self::Output {
+        BigInt::from_parts(self.negative != other.negative, mul_magnitude(&self.limbs, &other.limbs))
+    }
+}
+
+impl Mul for BigInt {
+    type Output = BigInt;
+
+    fn mul(self, other: BigInt) -> Self::Output {
+        &self * &other
+    }
+}
+
+impl Sum for BigInt {
+    fn sum<I: Iterator<Item = BigInt>>(iter: I) -> Self {
+        iter.fold(BigInt::zero(), |acc, x| acc + x)
+    }
+}
+
+impl<'a> Sum<&'a BigInt> for BigInt {
+    fn sum<I: Iterator<Item = &'a BigInt>>(iter: I) -> Self {
+        iter.fold(BigInt::zero(), |mut acc, x| {
+            acc += x;
+            acc
+        })
+    }
+}
+
+impl Product for BigInt {
+    fn product<I: Iterator<Item = BigInt>>(iter: I) -> Self {
+        iter.fold(BigInt::from(1u64), |acc, x| acc * x)
+    }
+}
+
+impl<'a> Product<&'a BigInt> for BigInt {
+    fn product<I: Iterator<Item = &'a BigInt>>(iter: I) -> Self {
+        iter.fold(BigInt::from(1u64), |acc, x| &acc * x)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use crate::{bigint::BigInt, rng::Rng};
+
+    fn big(s: &str) -> BigInt {
+        BigInt::from_str(s).unwrap()
+    }
+
+    #[test]
+    fn parse_and_display() {
+        assert_eq!(big("0").to_string(), "0");
+        assert_eq!(big("-0").to_string(), "0");
+        assert_eq!(big("000123").to_string(), "123");
+        assert_eq!(big("-1000000000").to_string(), "-1000000000");
+        assert_eq!(
+            big("123456789012345678901234567890").to_string(),
+            "123456789012345678901234567890",
+        );
+        assert_eq!(format!("{:05}", big("-12")), "-0012");
+        assert!(BigInt::from_str("").is_err());
+        assert!(BigInt::from_str("-").is_err());
+        assert!(BigInt::from_str("12a").is_err());
+    }
+
+    #[test]
+    fn arithmetic_beyond_u64() {
+        let a = big("99999999999999999999999");
+
+        assert_eq!((&a + &big("1")).to_string(), "100000000000000000000000");
+        assert_eq!((&a * &a).to_string(), "9999999999999999999999800000000000000000000001");
+        assert_eq!((&big("5") - &a).to_string(), "-99999999999999999999994");
+        assert_eq!(big("18446744073709551615").to_u64(), Some(u64::MAX));
+        assert_eq!(big("18446744073709551616").to_u64(), None);
+        assert!(big("-3") < big("2"));
+        assert!(big("-30") < big("-3"));
+        assert_eq!(big("2").pow(100).to_string(), "1267650600228229401496703205376");
+        assert_eq!(big("-3").pow(3).to_string(), "-27");
+        assert_eq!(big("0").pow(0).to_string(), "1");
+    }
+
+    #[test]
+    fn matches_i128() {
+        let mut rng = Rng::new(11);
+
+        for _ in 0..2000 {
+            let a = rng.next_u64() as i64 as i128;
+            let b = (rng.next_u64() >> rng.below(64)) as i64 as i128;
+            let (x, y) = (BigInt::from(a as i64), BigInt::from(b as i64));
+
+            assert_eq!((&x + &y).to_string(), (a + b).to_string());
+            assert_eq!((&x - &y).to_string(), (a - b).to_string());
+            assert_eq!((&x * &y).to_string(), (a * b).to_string());
+            assert_eq!(x.cmp(&y), a.cmp(&b));
+        }
+    }
+
+    #[test]
+    fn sum_and_product() {
+        let values = [big("10000000000000000000"), big("-1"), big("3")];
+
+        assert_eq!(values.iter().sum::<BigInt>().to_string(), "10000000000000000002");
+        assert_eq!(values.iter().product::<BigInt>().to_string(), "-30000000000000000000");
+        assert_eq!(Vec::<BigInt>::new().into_iter().product::<BigInt>().to_string(), "1");
+    }
+}
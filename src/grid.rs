@@ -0,0 +1,357 @@
+use std::{ops::{Index, IndexMut}, str::FromStr};
+
+const ORTHOGONAL: [(i64, i64); 4] = [
+    (0, -1),
+    (-1, 0),
+    (1, 0),
+    (0, 1),
+];
+
+// Row-major, so neighbours on the same row come out next to each other
+const SURROUNDING: [(i64, i64); 8] = [
+    (-1, -1), (0, -1), (1, -1),
+    (-1, 0),           (1, 0),
+    (-1, 1),  (0, 1),  (1, 1),
+];
+
+/**
+ * Which cells around a position count as its neighbours
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Neighborhood {
+    /**
+     * Up, left, right and down
+     */
+    VonNeumann,
+    /**
+     * All 8 surrounding cells
+     */
+    #[default]
+    Moore,
+    /**
+     * Every cell within this many orthogonal steps
+     */
+    Manhattan(usize),
+    /**
+     * Every cell within this many king moves, a (2k + 1) square
+     */
+    Chebyshev(usize),
+}
+
+impl Neighborhood {
+    /**
+     * How far away from the center a neighbour can be on either axis
+     */
+    pub fn radius(&self) -> usize {
+        match self {
+            Neighborhood::VonNeumann | Neighborhood::Moore => 1,
+            Neighborhood::Manhattan(k) | Neighborhood::Chebyshev(k) => *k,
+        }
+    }
+
+    /**
+     * Offsets of the neighbours in row-major order, without the center
+     */
+    pub fn offsets(&self) -> Vec<(i64, i64)> {
+        match self {
+            Neighborhood::VonNeumann => ORTHOGONAL.to_vec(),
+            Neighborhood::Moore => SURROUNDING.to_vec(),
+            Neighborhood::Manhattan(k) | Neighborhood::Chebyshev(k) => {
+                let k = *k as i64;
+
+                (-k..=k)
+                    .flat_map(|dy| (-k..=k).map(move |dx| (dx, dy)))
+                    .filter(|&(dx, dy)| (dx, dy) != (0, 0))
+                    .filter(|&(dx, dy)| matches!(self, Neighborhood::Chebyshev(_)) || dx.abs() + dy.abs() <= k)
+                    .collect()
+            },
+        }
+    }
+}
+
+/**
+ * `4`, `8`, `von-neumann`, `moore`, `manhattan:k` or `chebyshev:k`
+ */
+impl FromStr for Neighborhood {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim().to_lowercase();
+
+        let radius = |k: &str| k
+            .parse::<usize>()
+            .map_err(|_| format!("Failed to parse neighborhood radius: {}", k));
+
+        match s.split_once(':') {
+            None if s == "4" || s == "von-neumann" => Ok(Neighborhood::VonNeumann),
+            None if s == "8" || s == "moore" => Ok(Neighborhood::Moore),
+            Some(("manhattan", k)) => Ok(Neighborhood::Manhattan(radius(k)?)),
+            Some(("chebyshev", k)) => Ok(Neighborhood::Chebyshev(radius(k)?)),
+            _ => Err(format!("Unknown neighborhood: {}", s)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    pub fn new(width: usize, height: usize, cells: Vec<T>) -> Result<Self, String> {
+        if cells.len() != width * height {
+            return Err(
+                format!("Expected {} cells for a {}x{} grid, got {}", width * height, width, height, cells.len())
+            );
+        }
+
+        Ok(Self { width, height, cells })
+    }
+
+    /**
+     * Builds a grid out of rows, every row has to be as wide as the first one
+     */
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Result<Self, String> {
+        let height = rows.len();
+        let width = rows
+            .first()
+            .ok_or("Grid should have at least one row")?
+            .len();
+
+        if let Some((y, row)) = rows.iter().enumerate().find(|(_, row)| row.len() != width) {
+            return Err(
+                format!("Row {} has a width of {}, expected {}", y + 1, row.len(), width)
+            );
+        }
+
+        let cells = rows.into_iter().flatten().collect();
+
+        Self::new(width, height, cells)
+    }
+
+    /**
+     * Parses one cell per character, one row per line
+     */
+    pub fn parse<F>(s: &str, mut f: F) -> Result<Self, String>
+    where
+        F: FnMut(char) -> T,
+    {
+        let rows = s
+            .trim()
+            .lines()
+            .map(|line| line.chars().map(&mut f).collect())
+            .collect();
+
+        Self::from_rows(rows)
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn cells(&self) -> &[T] {
+        &self.cells
+    }
+
+    pub fn index_of(&self, x: usize, y: usize) -> usize {
+        y * self.width + x
+    }
+
+    pub fn position_of(&self, i: usize) -> (usize, usize) {
+        (i % self.width, i / self.width)
+    }
+
+    pub fn contains(&self, x: i64, y: i64) -> bool {
+        x >= 0 && y >= 0 && x < self.width as i64 && y < self.height as i64
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Option<&T> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+
+        self.cells.get(self.index_of(x, y))
+    }
+
+    pub fn get_mut(&mut self, x: usize, y: usize) -> Option<&mut T> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+
+        let i = self.index_of(x, y);
+
+        self.cells.get_mut(i)
+    }
+
+    /**
+     * Up, left, right and down neighbours that are inside the grid
+     */
+    pub fn neighbors4(&self, x: usize, y: usize) -> impl Iterator<Item = (usize, usize)> {
+        self.offset_neighbors(x, y, ORTHOGONAL)
+    }
+
+    /**
+     * All 8 surrounding neighbours that are inside the grid, in row-major order
+     */
+    pub fn neighbors8(&self, x: usize, y: usize) -> impl Iterator<Item = (usize, usize)> {
+        self.offset_neighbors(x, y, SURROUNDING)
+    }
+
+    /**
+     * Neighbours inside the grid for any neighbourhood, in row-major order
+     */
+    pub fn neighbors(&self, x: usize, y: usize, neighborhood: &Neighborhood) -> impl Iterator<Item = (usize, usize)> {
+        self.offset_neighbors(x, y, neighborhood.offsets())
+    }
+
+    fn offset_neighbors<O>(&self, x: usize, y: usize, offsets: O) -> impl Iterator<Item = (usize, usize)>
+    where
+        O: IntoIterator<Item = (i64, i64)>,
+    {
+        let (width, height) = (self.width as i64, self.height as i64);
+
+        offsets
+            .into_iter()
+            .map(move |(dx, dy)| (x as i64 + dx, y as i64 + dy))
+            .filter(move |&(x, y)| x >= 0 && y >= 0 && x < width && y < height)
+            .map(|(x, y)| (x as usize, y as usize))
+    }
+
+    pub fn row(&self, y: usize) -> &[T] {
+        let start = self.index_of(0, y);
+
+        &self.cells[start..(start + self.width)]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.cells.chunks(self.width)
+    }
+
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> {
+        assert!(x < self.width, "Column {} is out of bounds", x);
+
+        self.cells.iter().skip(x).step_by(self.width)
+    }
+
+    /**
+     * Every cell along with its (x, y) position, in row-major order
+     */
+    pub fn iter(&self) -> impl Iterator<Item = ((usize, usize), &T)> {
+        let width = self.width;
+
+        self.cells
+            .iter()
+            .enumerate()
+            .map(move |(i, cell)| ((i % width, i / width), cell))
+    }
+}
+
+impl<T> Index<(usize, usize)> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, (x, y): (usize, usize)) -> &Self::Output {
+        assert!(x < self.width && y < self.height, "({}, {}) is out of bounds", x, y);
+
+        &self.cells[self.index_of(x, y)]
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Grid<T> {
+    fn index_mut(&mut self, (x, y): (usize, usize)) -> &mut Self::Output {
+        assert!(x < self.width && y < self.height, "({}, {}) is out of bounds", x, y);
+
+        let i = self.index_of(x, y);
+
+        &mut self.cells[i]
+    }
+}
+
+impl FromStr for Grid<char> {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s, |c| c)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use crate::grid::{Grid, Neighborhood};
+
+    #[test]
+    fn parse_and_index() {
+        let grid = Grid::from_str("abc\ndef\n").unwrap();
+
+        assert_eq!(grid.width(), 3);
+        assert_eq!(grid.height(), 2);
+        assert_eq!(grid[(0, 0)], 'a');
+        assert_eq!(grid[(2, 1)], 'f');
+        assert_eq!(grid.get(3, 0), None);
+        assert_eq!(grid.position_of(4), (1, 1));
+    }
+
+    #[test]
+    fn rejects_ragged_rows() {
+        let err = Grid::from_str("abc\nde\nfgh").unwrap_err();
+
+        assert_eq!(err, "Row 2 has a width of 2, expected 3");
+    }
+
+    #[test]
+    fn neighbors_stay_in_bounds() {
+        let grid = Grid::from_str("abc\ndef\nghi").unwrap();
+
+        assert_eq!(grid.neighbors4(0, 0).collect::<Vec<_>>(), vec![(1, 0), (0, 1)]);
+        assert_eq!(
+            grid.neighbors8(2, 0).collect::<Vec<_>>(),
+            vec![(1, 0), (1, 1), (2, 1)],
+        );
+        assert_eq!(grid.neighbors8(1, 1).count(), 8);
+    }
+
+    #[test]
+    fn rows_and_columns() {
+        let grid = Grid::from_str("abc\ndef").unwrap();
+
+        assert_eq!(grid.row(1), &['d', 'e', 'f']);
+        assert_eq!(grid.rows().count(), 2);
+        assert_eq!(grid.column(1).collect::<String>(), "be");
+    }
+
+    #[test]
+    fn neighborhoods() {
+        let grid = Grid::parse(&".....\n".repeat(5), |c| c).unwrap();
+
+        assert_eq!(grid.neighbors(2, 2, &Neighborhood::VonNeumann).count(), 4);
+        assert_eq!(grid.neighbors(2, 2, &Neighborhood::Moore).count(), 8);
+        assert_eq!(grid.neighbors(2, 2, &Neighborhood::Manhattan(2)).count(), 12);
+        assert_eq!(grid.neighbors(2, 2, &Neighborhood::Chebyshev(2)).count(), 24);
+        assert_eq!(grid.neighbors(0, 0, &Neighborhood::Chebyshev(2)).count(), 8);
+        assert_eq!(
+            grid.neighbors(2, 2, &Neighborhood::Manhattan(1)).collect::<Vec<_>>(),
+            grid.neighbors4(2, 2).collect::<Vec<_>>(),
+        );
+        assert_eq!(
+            grid.neighbors(1, 1, &Neighborhood::Chebyshev(1)).collect::<Vec<_>>(),
+            grid.neighbors8(1, 1).collect::<Vec<_>>(),
+        );
+    }
+
+    #[test]
+    fn parse_neighborhood() {
+        assert_eq!(Neighborhood::from_str("4"), Ok(Neighborhood::VonNeumann));
+        assert_eq!(Neighborhood::from_str("Moore"), Ok(Neighborhood::Moore));
+        assert_eq!(Neighborhood::from_str("manhattan:3"), Ok(Neighborhood::Manhattan(3)));
+        assert_eq!(Neighborhood::from_str("chebyshev:2"), Ok(Neighborhood::Chebyshev(2)));
+        assert!(Neighborhood::from_str("chebyshev:x").is_err());
+        assert!(Neighborhood::from_str("hex").is_err());
+    }
+}
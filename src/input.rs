@@ -0,0 +1,48 @@
+use std::{env, fs};
+
+/**
+ * Where a solution reads its puzzle input from: the path after `--input` if
+ * there is one, `./input` otherwise. Keeps the example and real inputs
+ * switchable without editing the solutions.
+ */
+pub fn input_path() -> String {
+    arg_value("--input").unwrap_or_else(|| "./input".to_owned())
+}
+
+/**
+ * Value following a `--name value` command line flag
+ */
+pub fn arg_value(name: &str) -> Option<String> {
+    value_from_args(env::args(), name)
+}
+
+/**
+ * Reads the puzzle input, see `input_path`
+ */
+pub fn read_input() -> String {
+    let path = input_path();
+
+    fs::read_to_string(&path).unwrap_or_else(|err| panic!("Failed to read {}: {}", path, err))
+}
+
+fn value_from_args<I: Iterator<Item = String>>(mut args: I, name: &str) -> Option<String> {
+    args.find(|arg| arg == name)?;
+    args.next()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::input::value_from_args;
+
+    #[test]
+    fn flag_values() {
+        let args = |args: &[&str]| args.iter().map(|arg| arg.to_string()).collect::<Vec<_>>().into_iter();
+
+        assert_eq!(value_from_args(args(&["day05"]), "--input"), None);
+        assert_eq!(value_from_args(args(&["day05", "--input"]), "--input"), None);
+        assert_eq!(
+            value_from_args(args(&["day05", "--input", "exampleinput", "--pad"]), "--input"),
+            Some("exampleinput".to_owned()),
+        );
+    }
+}
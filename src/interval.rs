@@ -0,0 +1,419 @@
+use std::{iter, ops::Range};
+
+/**
+ * A set of u64s stored as sorted, disjoint, non-adjacent half-open ranges
+ */
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IntervalSet {
+    ranges: Vec<Range<u64>>,
+}
+
+impl IntervalSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn ranges(&self) -> &[Range<u64>] {
+        &self.ranges
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    /**
+     * Number of values in the set
+     */
+    pub fn len(&self) -> u64 {
+        self.ranges.iter().map(|r| r.end - r.start).sum()
+    }
+
+    pub fn min(&self) -> Option<u64> {
+        self.ranges.first().map(|r| r.start)
+    }
+
+    pub fn contains(&self, x: u64) -> bool {
+        let i = self.ranges.partition_point(|r| r.end <= x);
+
+        self.ranges.get(i).is_some_and(|r| r.start <= x)
+    }
+
+    pub fn insert(&mut self, range: Range<u64>) {
+        if range.is_empty() {
+            return;
+        }
+
+        let i = self.ranges.partition_point(|r| r.end < range.start);
+        let j = self.ranges.partition_point(|r| r.start <= range.end);
+
+        // Everything in i..j touches the new range and gets merged into it
+        let (mut start, mut end) = (range.start, range.end);
+
+        if i < j {
+            start = start.min(self.ranges[i].start);
+            end = end.max(self.ranges[j - 1].end);
+        }
+
+        self.ranges.splice(i..j, iter::once(start..end));
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        let mut union = self.clone();
+
+        for range in other.ranges.iter() {
+            union.insert(range.clone());
+        }
+
+        union
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        let (mut i, mut j) = (0, 0);
+        let mut ranges = vec![];
+
+        while i < self.ranges.len() && j < other.ranges.len() {
+            let (a, b) = (&self.ranges[i], &other.ranges[j]);
+            let (start, end) = (a.start.max(b.start), a.end.min(b.end));
+
+            if start < end {
+                ranges.push(start..end);
+            }
+
+            if a.end < b.end {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+
+        Self { ranges }
+    }
+
+    /**
+     * Values in `self` that aren't in `other`
+     */
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut ranges = vec![];
+
+        for range in self.ranges.iter() {
+            let mut start = range.start;
+
+            for cut in other.overlapping(range) {
+                if start < cut.start {
+                    ranges.push(start..cut.start);
+                }
+
+                start = start.max(cut.end);
+            }
+
+            if start < range.end {
+                ranges.push(start..range.end);
+            }
+        }
+
+        Self { ranges }
+    }
+
+    fn overlapping<'a>(&'a self, range: &'a Range<u64>) -> impl Iterator<Item = &'a Range<u64>> + 'a {
+        let i = self.ranges.partition_point(|r| r.end <= range.start);
+
+        self.ranges[i..].iter().take_while(move |r| r.start < range.end)
+    }
+}
+
+impl FromIterator<Range<u64>> for IntervalSet {
+    fn from_iter<I: IntoIterator<Item = Range<u64>>>(iter: I) -> Self {
+        let mut set = Self::new();
+
+        for range in iter {
+            set.insert(range);
+        }
+
+        set
+    }
+}
+
+/**
+ * The `len` values from `start` on, or an error naming the range when they
+ * run past `u64::MAX`
+ */
+pub fn checked_range(name: &str, start: u64, len: u64) -> Result<Range<u64>, String> {
+    start
+        .checked_add(len)
+        .map(|end| start..end)
+        .ok_or_else(|| format!("{} range starting at {} with a length of {} overflows u64", name, start, len))
+}
+
+/**
+ * A piecewise map of u64s: every piece shifts a range of sources onto a
+ * range of destinations of the same length, anything no piece covers maps to
+ * itself. Pieces are kept sorted by source and never overlap.
+ */
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RangeMap {
+    /**
+     * Sources and the destination their start maps to
+     */
+    pieces: Vec<(Range<u64>, u64)>,
+}
+
+impl RangeMap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn pieces(&self) -> &[(Range<u64>, u64)] {
+        &self.pieces
+    }
+
+    /**
+     * Maps the `len` values from `source` on onto the ones from `destination`
+     * on. Empty pieces are ignored, pieces overlapping an existing one and
+     * ranges past `u64::MAX` are errors.
+     */
+    pub fn insert(&mut self, source: u64, destination: u64, len: u64) -> Result<(), String> {
+        if len == 0 {
+            return Ok(());
+        }
+
+        let source = checked_range("Source", source, len)?;
+        checked_range("Destination", destination, len)?;
+
+        let i = self.pieces.partition_point(|(r, _)| r.end <= source.start);
+
+        if let Some((r, _)) = self.pieces.get(i).filter(|(r, _)| r.start < source.end) {
+            return Err(format!("{:?} overlaps {:?}", source, r));
+        }
+
+        self.pieces.insert(i, (source, destination));
+
+        Ok(())
+    }
+
+    fn piece_at(&self, x: u64) -> Option<&(Range<u64>, u64)> {
+        let i = self.pieces.partition_point(|(r, _)| r.end <= x);
+
+        self.pieces.get(i).filter(|(r, _)| r.start <= x)
+    }
+
+    pub fn get(&self, x: u64) -> u64 {
+        match self.piece_at(x) {
+            Some((r, destination)) => destination + (x - r.start),
+            None => x,
+        }
+    }
+
+    /**
+     * Every source that maps to `y`, sorted. Pieces can send different sources
+     * to the same value and `y` maps to itself unless a piece covers it, so
+     * there can be several of them or none at all.
+     */
+    pub fn preimage(&self, y: u64) -> Vec<u64> {
+        let mut sources: Vec<u64> = self.pieces
+            .iter()
+            .filter(|(r, destination)| *destination <= y && y - destination < r.end - r.start)
+            .map(|(r, destination)| r.start + (y - destination))
+            .collect();
+
+        if self.piece_at(y).is_none() {
+            sources.push(y);
+        }
+
+        sources.sort_unstable();
+
+        sources
+    }
+
+    /**
+     * Splits `range` wherever it crosses the edge of a piece, with where the
+     * start of each part maps to, or `None` for parts that map to themselves
+     */
+    pub fn split(&self, range: Range<u64>) -> Vec<(Range<u64>, Option<u64>)> {
+        let mut parts = vec![];
+        let mut start = range.start;
+        let i = self.pieces.partition_point(|(r, _)| r.end <= range.start);
+
+        for (r, destination) in self.pieces[i..].iter().take_while(|(r, _)| r.start < range.end) {
+            if start < r.start {
+                parts.push((start..r.start, None));
+                start = r.start;
+            }
+
+            let end = r.end.min(range.end);
+            parts.push((start..end, Some(destination + (start - r.start))));
+            start = end;
+        }
+
+        if start < range.end {
+            parts.push((start..range.end, None));
+        }
+
+        parts
+    }
+
+    /**
+     * Where all the values of `set` end up
+     */
+    pub fn apply(&self, set: &IntervalSet) -> IntervalSet {
+        set.ranges()
+            .iter()
+            .flat_map(|range| self.split(range.clone()))
+            .map(|(part, destination)| match destination {
+                Some(d) => d..(d + (part.end - part.start)),
+                None => part,
+            })
+            .collect()
+    }
+
+    /**
+     * A map that takes a value through `first` and then through `self` in one
+     * step. Values neither of them maps are left out, so they still map to
+     * themselves.
+     */
+    pub fn compose(&self, first: &RangeMap) -> RangeMap {
+        let end = self.pieces
+            .iter()
+            .chain(first.pieces.iter())
+            .map(|(r, destination)| r.end.max(destination + (r.end - r.start)))
+            .max()
+            .unwrap_or(0);
+
+        let mut composed = RangeMap::new();
+
+        for (part, middle) in first.split(0..end) {
+            let len = part.end - part.start;
+            let middle_start = middle.unwrap_or(part.start);
+
+            for (second, destination) in self.split(middle_start..(middle_start + len)) {
+                // Only identity on both sides, nothing to store
+                if middle.is_none() && destination.is_none() {
+                    continue;
+                }
+
+                let source = part.start + (second.start - middle_start);
+
+                composed.pieces.push((
+                    source..(source + (second.end - second.start)),
+                    destination.unwrap_or(second.start),
+                ));
+            }
+        }
+
+        composed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{interval::{IntervalSet, RangeMap}, rng::Rng};
+
+    fn set(ranges: &[(u64, u64)]) -> IntervalSet {
+        ranges.iter().map(|(start, end)| *start..*end).collect()
+    }
+
+    fn random_set(rng: &mut Rng) -> IntervalSet {
+        (0..rng.below(5))
+            .map(|_| {
+                let start = rng.below(100);
+                start..(start + rng.below(20))
+            })
+            .collect()
+    }
+
+    fn random_map(rng: &mut Rng) -> RangeMap {
+        let mut map = RangeMap::new();
+        let mut start = rng.below(10);
+
+        for _ in 0..rng.below(5) {
+            let len = rng.range(1, 20);
+            map.insert(start, rng.below(100), len).unwrap();
+            start += len + rng.below(10);
+        }
+
+        map
+    }
+
+    #[test]
+    fn insert_merges_ranges() {
+        let set = set(&[(5, 10), (20, 30), (10, 12), (0, 0), (28, 40), (1, 3)]);
+
+        assert_eq!(set.ranges(), &[1..3, 5..12, 20..40]);
+        assert_eq!(set.len(), 2 + 7 + 20);
+        assert_eq!(set.min(), Some(1));
+        assert!(set.contains(11) && ! set.contains(12) && ! set.contains(3));
+    }
+
+    #[test]
+    fn set_operations_match_membership() {
+        let mut rng = Rng::new(9);
+
+        for _ in 0..300 {
+            let (a, b) = (random_set(&mut rng), random_set(&mut rng));
+            let (union, intersection, difference) = (a.union(&b), a.intersection(&b), a.difference(&b));
+
+            for x in 0..130 {
+                assert_eq!(union.contains(x), a.contains(x) || b.contains(x));
+                assert_eq!(intersection.contains(x), a.contains(x) && b.contains(x));
+                assert_eq!(difference.contains(x), a.contains(x) && ! b.contains(x));
+            }
+
+            assert_eq!(union.len(), a.len() + b.len() - intersection.len());
+        }
+    }
+
+    #[test]
+    fn range_map_lookups() {
+        let mut map = RangeMap::new();
+        map.insert(10, 100, 5).unwrap();
+        map.insert(0, 12, 5).unwrap();
+
+        assert!(map.insert(4, 0, 2).is_err());
+        assert!(map.insert(20, u64::MAX, 2).is_err());
+        assert!(map.insert(u64::MAX, 0, 1).is_err());
+        assert!(map.insert(20, 0, 0).is_ok());
+
+        assert_eq!(map.get(3), 15);
+        assert_eq!(map.get(14), 104);
+        assert_eq!(map.get(7), 7);
+        assert_eq!(map.preimage(12), vec![0]);
+        assert_eq!(map.preimage(14), vec![2]);
+        assert_eq!(map.preimage(8), vec![8]);
+        assert_eq!(
+            map.split(3..12),
+            vec![(3..5, Some(15)), (5..10, None), (10..12, Some(100))],
+        );
+        assert_eq!(map.apply(&set(&[(3, 12)])), set(&[(5, 10), (15, 17), (100, 102)]));
+    }
+
+    #[test]
+    fn compose_matches_lookups() {
+        let mut rng = Rng::new(10);
+
+        for _ in 0..300 {
+            let (first, second) = (random_map(&mut rng), random_map(&mut rng));
+            let composed = second.compose(&first);
+
+            for x in 0..250 {
+                assert_eq!(composed.get(x), second.get(first.get(x)));
+            }
+
+            let set = random_set(&mut rng);
+            assert_eq!(composed.apply(&set), second.apply(&first.apply(&set)));
+        }
+    }
+
+    #[test]
+    fn preimage_inverts_get() {
+        let mut rng = Rng::new(12);
+
+        for _ in 0..100 {
+            let map = random_map(&mut rng);
+
+            for y in 0..150 {
+                let expected = (0..300).filter(|x| map.get(*x) == y).collect::<Vec<_>>();
+
+                assert_eq!(map.preimage(y).into_iter().filter(|x| *x < 300).collect::<Vec<_>>(), expected);
+            }
+        }
+    }
+}
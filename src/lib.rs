@@ -0,0 +1,7 @@
+pub mod bigint;
+pub mod grid;
+pub mod input;
+pub mod interval;
+pub mod log;
+pub mod rng;
+pub mod text;
@@ -0,0 +1,126 @@
+use std::{env, str::FromStr, sync::OnceLock};
+
+use crate::input::arg_value;
+
+/**
+ * How much a solution tells about what it's doing, every level includes the
+ * ones above it
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    Off,
+    Error,
+    Warn,
+    Info,
+    Debug,
+    Trace,
+}
+
+impl FromStr for Level {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "off" => Ok(Self::Off),
+            "error" => Ok(Self::Error),
+            "warn" => Ok(Self::Warn),
+            "info" => Ok(Self::Info),
+            "debug" => Ok(Self::Debug),
+            "trace" => Ok(Self::Trace),
+            _ => Err(format!("Unknown log level: {}", s)),
+        }
+    }
+}
+
+impl Level {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Off => "off",
+            Self::Error => "error",
+            Self::Warn => "warn",
+            Self::Info => "info",
+            Self::Debug => "debug",
+            Self::Trace => "trace",
+        }
+    }
+}
+
+static LEVEL: OnceLock<Level> = OnceLock::new();
+
+/**
+ * The level after `--log` if there is one, `AOC_LOG` otherwise, and nothing
+ * gets logged if neither is set. Read once, the first time something is
+ * logged.
+ */
+pub fn level() -> Level {
+    *LEVEL.get_or_init(|| {
+        level_from(arg_value("--log"), env::var("AOC_LOG").ok()).unwrap_or_else(|err| {
+            eprintln!("{}, logging is off", err);
+            Level::Off
+        })
+    })
+}
+
+pub fn enabled(level: Level) -> bool {
+    level != Level::Off && level <= self::level()
+}
+
+fn level_from(flag: Option<String>, var: Option<String>) -> Result<Level, String> {
+    flag.or(var).map_or(Ok(Level::Off), |level| Level::from_str(&level))
+}
+
+/**
+ * Writes a line to stderr if `level` is enabled, so logs never mix with the
+ * answers on stdout. The arguments aren't formatted when it isn't.
+ */
+#[macro_export]
+macro_rules! log {
+    ($level:expr, $($arg:tt)+) => {
+        if $crate::log::enabled($level) {
+            eprintln!("[{}] {}", $level.name(), format_args!($($arg)+));
+        }
+    };
+}
+
+#[macro_export]
+macro_rules! error {
+    ($($arg:tt)+) => { $crate::log!($crate::log::Level::Error, $($arg)+) };
+}
+
+#[macro_export]
+macro_rules! warn {
+    ($($arg:tt)+) => { $crate::log!($crate::log::Level::Warn, $($arg)+) };
+}
+
+#[macro_export]
+macro_rules! info {
+    ($($arg:tt)+) => { $crate::log!($crate::log::Level::Info, $($arg)+) };
+}
+
+#[macro_export]
+macro_rules! debug {
+    ($($arg:tt)+) => { $crate::log!($crate::log::Level::Debug, $($arg)+) };
+}
+
+#[macro_export]
+macro_rules! trace {
+    ($($arg:tt)+) => { $crate::log!($crate::log::Level::Trace, $($arg)+) };
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use crate::log::{level_from, Level};
+
+    #[test]
+    fn flag_overrides_env() {
+        let some = |s: &str| Some(s.to_owned());
+
+        assert_eq!(level_from(None, None), Ok(Level::Off));
+        assert_eq!(level_from(None, some("debug")), Ok(Level::Debug));
+        assert_eq!(level_from(some("TRACE"), some("debug")), Ok(Level::Trace));
+        assert!(level_from(some("loud"), None).is_err());
+        assert!(Level::Warn < Level::Debug && Level::from_str("info").unwrap() > Level::Error);
+    }
+}
@@ -0,0 +1,68 @@
+/**
+ * Small xorshift generator for building random puzzle inputs in tests and
+ * benchmarks. Deterministic for a given seed, not meant for anything else.
+ */
+#[derive(Debug, Clone)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        // A zero state would only ever produce zeros
+        Self { state: seed ^ 0x9e37_79b9_7f4a_7c15 }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        let mut x = self.state;
+
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+
+        self.state = x;
+
+        x
+    }
+
+    /**
+     * Uniform-ish value in `0..n`
+     */
+    pub fn below(&mut self, n: u64) -> u64 {
+        assert!(n > 0, "Range should not be empty");
+
+        self.next_u64() % n
+    }
+
+    pub fn range(&mut self, start: u64, end: u64) -> u64 {
+        start + self.below(end - start)
+    }
+
+    pub fn chance(&mut self, numerator: u64, denominator: u64) -> bool {
+        self.below(denominator) < numerator
+    }
+
+    pub fn pick<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.below(items.len() as u64) as usize]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::rng::Rng;
+
+    #[test]
+    fn deterministic_and_in_range() {
+        let mut a = Rng::new(7);
+        let mut b = Rng::new(7);
+
+        for _ in 0..1000 {
+            let x = a.range(10, 20);
+
+            assert_eq!(x, b.range(10, 20));
+            assert!((10..20).contains(&x));
+        }
+
+        assert_ne!(Rng::new(0).next_u64(), 0);
+    }
+}
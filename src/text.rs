@@ -0,0 +1,112 @@
+/**
+ * Splits a line into user-perceived characters, so a symbol made of several
+ * code points (an accented letter, an emoji with a variation selector, a flag,
+ * ...) takes up a single column.
+ *
+ * This is an approximation of Unicode extended grapheme clusters that covers
+ * combining marks, variation selectors, zero width joiner sequences, emoji
+ * modifiers, tag sequences and regional indicator pairs. Good enough for
+ * puzzle inputs, not for general text.
+ */
+pub fn graphemes(s: &str) -> Vec<&str> {
+    let mut clusters: Vec<&str> = vec![];
+    let mut start = 0;
+    let mut prev: Option<char> = None;
+    let mut regional_indicators = 0;
+
+    for (i, c) in s.char_indices() {
+        let joins = match prev {
+            None => false,
+            Some(p) => {
+                is_extending(c)
+                    || p == ZWJ
+                    || (is_regional_indicator(c) && is_regional_indicator(p) && regional_indicators % 2 == 1)
+            },
+        };
+
+        if ! joins && i > start {
+            clusters.push(&s[start..i]);
+            start = i;
+        }
+
+        if ! joins {
+            regional_indicators = 0;
+        }
+
+        if is_regional_indicator(c) {
+            regional_indicators += 1;
+        }
+
+        prev = Some(c);
+    }
+
+    if start < s.len() {
+        clusters.push(&s[start..]);
+    }
+
+    clusters
+}
+
+const ZWJ: char = '\u{200D}';
+
+fn is_regional_indicator(c: char) -> bool {
+    ('\u{1F1E6}'..='\u{1F1FF}').contains(&c)
+}
+
+/**
+ * Code points that attach to whatever comes before them
+ */
+fn is_extending(c: char) -> bool {
+    matches!(
+        c,
+        '\u{0300}'..='\u{036F}'         // Combining diacritical marks
+        | '\u{0483}'..='\u{0489}'       // Cyrillic combining marks
+        | '\u{0591}'..='\u{05BD}'       // Hebrew points
+        | '\u{064B}'..='\u{065F}'       // Arabic marks
+        | '\u{0E31}' | '\u{0E34}'..='\u{0E3A}' | '\u{0E47}'..='\u{0E4E}' // Thai
+        | '\u{1AB0}'..='\u{1AFF}'       // Combining diacritical marks extended
+        | '\u{1DC0}'..='\u{1DFF}'       // Combining diacritical marks supplement
+        | '\u{200C}' | ZWJ
+        | '\u{20D0}'..='\u{20FF}'       // Combining marks for symbols, keycaps
+        | '\u{FE00}'..='\u{FE0F}'       // Variation selectors
+        | '\u{FE20}'..='\u{FE2F}'       // Combining half marks
+        | '\u{1F3FB}'..='\u{1F3FF}'     // Emoji skin tone modifiers
+        | '\u{E0020}'..='\u{E007F}'     // Tags
+        | '\u{E0100}'..='\u{E01EF}'     // Variation selectors supplement
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::text::graphemes;
+
+    #[test]
+    fn ascii_is_one_cluster_per_char() {
+        assert_eq!(graphemes("ab.*"), vec!["a", "b", ".", "*"]);
+        assert!(graphemes("").is_empty());
+    }
+
+    #[test]
+    fn multi_code_point_clusters() {
+        // e + combining acute, then a plain é
+        assert_eq!(graphemes("e\u{301}\u{e9}"), vec!["e\u{301}", "\u{e9}"]);
+        // Heavy eight spoked asterisk with emoji presentation
+        assert_eq!(graphemes("1\u{2733}\u{FE0F}2"), vec!["1", "\u{2733}\u{FE0F}", "2"]);
+        // Keycap one
+        assert_eq!(graphemes("1\u{FE0F}\u{20E3}."), vec!["1\u{FE0F}\u{20E3}", "."]);
+        // Woman + ZWJ + rocket
+        assert_eq!(graphemes("\u{1F469}\u{200D}\u{1F680}*"), vec!["\u{1F469}\u{200D}\u{1F680}", "*"]);
+        // Thumbs up with a skin tone
+        assert_eq!(graphemes("\u{1F44D}\u{1F3FD}"), vec!["\u{1F44D}\u{1F3FD}"]);
+    }
+
+    #[test]
+    fn flags_pair_up() {
+        let flags = "\u{1F1EF}\u{1F1F5}\u{1F1FA}\u{1F1F8}\u{1F1EB}";
+
+        assert_eq!(
+            graphemes(flags),
+            vec!["\u{1F1EF}\u{1F1F5}", "\u{1F1FA}\u{1F1F8}", "\u{1F1EB}"],
+        );
+    }
+}